@@ -3,6 +3,7 @@
 pub mod gemini;
 pub mod provider;
 pub mod roles;
+pub mod runner;
 
 pub use gemini::GeminiProvider;
 pub use provider::AiProvider;
@@ -0,0 +1,150 @@
+//! Running AI roles from hotkeys, post-actions and the recording archive
+
+use std::future::Future;
+
+use crate::audio;
+use crate::config::schema::{AiRole, AiSettings};
+use crate::error::AppError;
+
+use super::{AiProvider, GeminiProvider};
+
+/// Find a role by ID, falling back to the built-in roles
+pub fn find_role(settings: &AiSettings, role_id: &str) -> Result<AiRole, AppError> {
+    let builtin_roles = super::get_builtin_roles();
+    settings
+        .roles
+        .iter()
+        .find(|r| r.id == role_id)
+        .or_else(|| builtin_roles.iter().find(|r| r.id == role_id))
+        .cloned()
+        .ok_or_else(|| AppError::Ai(format!("Role not found: {}", role_id)))
+}
+
+/// Create a provider from the first configured provider
+pub fn create_provider(settings: &AiSettings) -> Result<GeminiProvider, AppError> {
+    let provider = settings
+        .providers
+        .first()
+        .ok_or_else(|| AppError::Ai("No AI provider configured".to_string()))?;
+
+    Ok(GeminiProvider::new(
+        provider.api_key.clone(),
+        provider.model.clone(),
+    ))
+}
+
+/// Run a role on text input and return the response text
+pub async fn run_text(
+    settings: &AiSettings,
+    role_id: &str,
+    text: &str,
+) -> Result<String, AppError> {
+    let role = find_role(settings, role_id)?;
+    let provider = create_provider(settings)?;
+    let response = provider.send_text(&role.system_prompt, text).await?;
+    Ok(response.text)
+}
+
+/// Run a role on encoded audio and return the response text
+pub async fn run_audio(
+    settings: &AiSettings,
+    role_id: &str,
+    audio_data: &[u8],
+    mime_type: &str,
+) -> Result<String, AppError> {
+    let role = find_role(settings, role_id)?;
+    let provider = create_provider(settings)?;
+    let response = provider
+        .send_audio(&role.system_prompt, audio_data, mime_type)
+        .await?;
+    Ok(response.text)
+}
+
+/// Number of characters of the previous chunk's transcript passed as context
+const CHUNK_CONTEXT_CHARS: usize = 500;
+
+/// Run a role on a sequence of encoded audio chunks and stitch the responses
+/// Each request carries the end of the previous chunk's response as context.
+/// `on_progress` is called with (chunk index, chunk count) before each request.
+pub async fn run_audio_chunks(
+    settings: &AiSettings,
+    role_id: &str,
+    chunks: &[(Vec<u8>, &str)],
+    mut on_progress: impl FnMut(usize, usize),
+) -> Result<String, AppError> {
+    let role = find_role(settings, role_id)?;
+    let provider = create_provider(settings)?;
+
+    let mut parts: Vec<String> = Vec::with_capacity(chunks.len());
+
+    for (index, (audio_data, mime_type)) in chunks.iter().enumerate() {
+        on_progress(index, chunks.len());
+
+        let system_prompt = match parts.last() {
+            Some(previous) => format!(
+                "{}\n\nThis audio is part {} of {} of a longer recording. \
+                 The previous part ended with:\n\"{}\"\n\
+                 Continue seamlessly from there and do not repeat it.",
+                role.system_prompt,
+                index + 1,
+                chunks.len(),
+                tail_chars(previous, CHUNK_CONTEXT_CHARS)
+            ),
+            None => role.system_prompt.clone(),
+        };
+
+        let response = provider
+            .send_audio(&system_prompt, audio_data, mime_type)
+            .await
+            .map_err(|e| {
+                AppError::Ai(format!(
+                    "Chunk {} of {} failed: {}",
+                    index + 1,
+                    chunks.len(),
+                    e
+                ))
+            })?;
+        parts.push(response.text.trim().to_string());
+    }
+
+    Ok(parts.join("\n"))
+}
+
+/// Split a long recording at silence boundaries and run a role on the chunks in order
+pub async fn run_long_audio(
+    settings: &AiSettings,
+    role_id: &str,
+    samples: &[f32],
+    sample_rate: u32,
+    channels: u16,
+    on_progress: impl FnMut(usize, usize),
+) -> Result<String, AppError> {
+    let ranges =
+        audio::chunker::split_at_silence(samples, sample_rate, channels, &settings.chunking);
+    eprintln!("Split recording into {} chunks", ranges.len());
+
+    let chunks = ranges
+        .into_iter()
+        .map(|range| audio::encode_with_fallback(&samples[range], sample_rate, channels))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    run_audio_chunks(settings, role_id, &chunks, on_progress).await
+}
+
+/// Get the last `count` characters of a string
+fn tail_chars(text: &str, count: usize) -> &str {
+    let start = text
+        .char_indices()
+        .rev()
+        .nth(count.saturating_sub(1))
+        .map(|(i, _)| i)
+        .unwrap_or(0);
+    &text[start..]
+}
+
+/// Block on an AI future from a non-async context (hotkey threads)
+pub fn block_on<T>(future: impl Future<Output = Result<T, AppError>>) -> Result<T, AppError> {
+    let rt = tokio::runtime::Runtime::new()
+        .map_err(|e| AppError::Ai(format!("Failed to create runtime: {}", e)))?;
+    rt.block_on(future)
+}
@@ -0,0 +1,240 @@
+//! Archive of recorded audio with size- and age-based retention
+//!
+//! Each recording is stored as two files in `<config_dir>/recordings/`:
+//! the encoded audio (`<id>.ogg` / `<id>.wav`) and its metadata (`<id>.json`).
+
+use std::fs;
+use std::path::PathBuf;
+
+use chrono::{Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::config::schema::RecordingArchiveSettings;
+use crate::error::AppError;
+
+const ARCHIVE_DIR_NAME: &str = "recordings";
+
+/// Metadata for an archived recording
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchivedRecording {
+    pub id: String,
+    pub created_at: String,
+    pub mime_type: String,
+    pub size_bytes: u64,
+    /// Role used for the most recent AI run
+    pub role_id: String,
+    /// AI result of the most recent run (None if it failed or is still pending)
+    #[serde(default)]
+    pub result: Option<String>,
+    /// Error of the most recent run, if it failed
+    #[serde(default)]
+    pub error: Option<String>,
+    #[serde(default)]
+    pub processed_at: Option<String>,
+}
+
+/// Get the archive directory path
+fn get_archive_dir() -> Result<PathBuf, AppError> {
+    Ok(crate::config::manager::get_config_dir()?.join(ARCHIVE_DIR_NAME))
+}
+
+/// Get the file extension for an audio MIME type
+fn extension_for_mime(mime_type: &str) -> &'static str {
+    match mime_type {
+        "audio/wav" => "wav",
+        _ => "ogg",
+    }
+}
+
+/// Check that an archive ID is a UUID before it becomes part of a file name
+/// IDs come from the frontend and from metadata files, so they can't be trusted.
+fn validate_id(id: &str) -> Result<(), AppError> {
+    uuid::Uuid::parse_str(id)
+        .map(|_| ())
+        .map_err(|_| AppError::Audio(format!("Invalid archived recording ID: {}", id)))
+}
+
+/// Get the audio file path for an archived recording
+fn get_audio_path(entry: &ArchivedRecording) -> Result<PathBuf, AppError> {
+    validate_id(&entry.id)?;
+    Ok(get_archive_dir()?.join(format!(
+        "{}.{}",
+        entry.id,
+        extension_for_mime(&entry.mime_type)
+    )))
+}
+
+/// Get the metadata file path for an archived recording
+fn get_metadata_path(id: &str) -> Result<PathBuf, AppError> {
+    validate_id(id)?;
+    Ok(get_archive_dir()?.join(format!("{}.json", id)))
+}
+
+/// Store an encoded recording in the archive
+/// Returns the archive ID, or None if archiving is disabled
+pub fn store(
+    settings: &RecordingArchiveSettings,
+    audio_data: &[u8],
+    mime_type: &str,
+    role_id: &str,
+) -> Result<Option<String>, AppError> {
+    if !settings.enabled {
+        return Ok(None);
+    }
+
+    let archive_dir = get_archive_dir()?;
+    if !archive_dir.exists() {
+        fs::create_dir_all(&archive_dir)?;
+    }
+
+    let entry = ArchivedRecording {
+        id: uuid::Uuid::new_v4().to_string(),
+        created_at: Utc::now().to_rfc3339(),
+        mime_type: mime_type.to_string(),
+        size_bytes: audio_data.len() as u64,
+        role_id: role_id.to_string(),
+        result: None,
+        error: None,
+        processed_at: None,
+    };
+
+    fs::write(get_audio_path(&entry)?, audio_data)?;
+    save_metadata(&entry)?;
+
+    if let Err(e) = apply_retention(settings) {
+        eprintln!("Failed to apply recording archive retention: {}", e);
+    }
+
+    Ok(Some(entry.id))
+}
+
+/// Record the outcome of an AI run for an archived recording
+pub fn record_result(
+    id: &str,
+    role_id: &str,
+    result: &Result<String, AppError>,
+) -> Result<(), AppError> {
+    let mut entry = load_metadata(id)?;
+
+    entry.role_id = role_id.to_string();
+    entry.processed_at = Some(Utc::now().to_rfc3339());
+    match result {
+        Ok(text) => {
+            entry.result = Some(text.clone());
+            entry.error = None;
+        }
+        Err(e) => {
+            entry.result = None;
+            entry.error = Some(e.to_string());
+        }
+    }
+
+    save_metadata(&entry)
+}
+
+/// List archived recordings, newest first
+pub fn list() -> Result<Vec<ArchivedRecording>, AppError> {
+    let archive_dir = get_archive_dir()?;
+    if !archive_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut entries = Vec::new();
+    for dir_entry in fs::read_dir(&archive_dir)? {
+        let path = dir_entry?.path();
+        if path.extension().is_some_and(|ext| ext == "json") {
+            let content = fs::read_to_string(&path)?;
+            match serde_json::from_str::<ArchivedRecording>(&content) {
+                // The ID names the entry's files, so it must match this one
+                Ok(entry)
+                    if validate_id(&entry.id).is_err()
+                        || path.file_stem().is_none_or(|stem| *stem != *entry.id) =>
+                {
+                    eprintln!("Skipping archive entry {:?} with mismatched ID", path)
+                }
+                Ok(entry) => entries.push(entry),
+                Err(e) => eprintln!("Skipping corrupted archive entry {:?}: {}", path, e),
+            }
+        }
+    }
+
+    // RFC 3339 timestamps sort chronologically
+    entries.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    Ok(entries)
+}
+
+/// Load the encoded audio and metadata of an archived recording
+pub fn load(id: &str) -> Result<(ArchivedRecording, Vec<u8>), AppError> {
+    let entry = load_metadata(id)?;
+    let audio_data = fs::read(get_audio_path(&entry)?)?;
+    Ok((entry, audio_data))
+}
+
+/// Delete an archived recording
+pub fn delete(id: &str) -> Result<(), AppError> {
+    let entry = load_metadata(id)?;
+    remove_entry(&entry)
+}
+
+/// Remove recordings that exceed the configured age or total size
+pub fn apply_retention(settings: &RecordingArchiveSettings) -> Result<(), AppError> {
+    // Newest first, so the oldest recordings are removed first when over the size limit
+    let entries = list()?;
+
+    // Limits too large to represent are treated as no limit
+    let max_age = i64::try_from(settings.max_age_days)
+        .ok()
+        .filter(|&days| days > 0)
+        .and_then(Duration::try_days);
+    let max_size = settings.max_size_mb.saturating_mul(1024 * 1024);
+    let now = Utc::now();
+    let mut total_size = 0u64;
+
+    for entry in entries {
+        let expired = max_age.is_some_and(|max_age| {
+            chrono::DateTime::parse_from_rfc3339(&entry.created_at)
+                .map(|created| now.signed_duration_since(created) > max_age)
+                .unwrap_or(false)
+        });
+
+        if expired {
+            remove_entry(&entry)?;
+            continue;
+        }
+
+        total_size += entry.size_bytes;
+        if max_size > 0 && total_size > max_size {
+            remove_entry(&entry)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn load_metadata(id: &str) -> Result<ArchivedRecording, AppError> {
+    let path = get_metadata_path(id)?;
+    if !path.exists() {
+        return Err(AppError::Audio(format!(
+            "Archived recording not found: {}",
+            id
+        )));
+    }
+    let content = fs::read_to_string(&path)?;
+    Ok(serde_json::from_str(&content)?)
+}
+
+fn save_metadata(entry: &ArchivedRecording) -> Result<(), AppError> {
+    let content = serde_json::to_string_pretty(entry)?;
+    fs::write(get_metadata_path(&entry.id)?, content)?;
+    Ok(())
+}
+
+fn remove_entry(entry: &ArchivedRecording) -> Result<(), AppError> {
+    let audio_path = get_audio_path(entry)?;
+    if audio_path.exists() {
+        fs::remove_file(audio_path)?;
+    }
+    fs::remove_file(get_metadata_path(&entry.id)?)?;
+    Ok(())
+}
@@ -0,0 +1,103 @@
+//! Splitting long recordings into chunks at silence boundaries
+
+use std::ops::Range;
+
+use crate::config::schema::ChunkingSettings;
+
+/// Analysis window for finding silence (20ms)
+const WINDOW_MS: usize = 20;
+
+/// Check if a recording is long enough to be split into chunks
+pub fn needs_chunking(
+    sample_count: usize,
+    sample_rate: u32,
+    channels: u16,
+    settings: &ChunkingSettings,
+) -> bool {
+    if settings.threshold_secs == 0 || settings.chunk_length_secs == 0 {
+        return false;
+    }
+    let duration_secs = sample_count / (sample_rate as usize * channels as usize).max(1);
+    duration_secs as u64 > settings.threshold_secs
+}
+
+/// Split interleaved samples into chunks of roughly `chunk_length_secs`
+/// Each cut is placed at the quietest window within `silence_search_secs` of the chunk mark
+pub fn split_at_silence(
+    samples: &[f32],
+    sample_rate: u32,
+    channels: u16,
+    settings: &ChunkingSettings,
+) -> Vec<Range<usize>> {
+    let channels = channels.max(1) as usize;
+    let frame_count = samples.len() / channels;
+    let window_frames = (sample_rate as usize * WINDOW_MS / 1000).max(1);
+    let chunk_frames = settings.chunk_length_secs as usize * sample_rate as usize;
+    let search_frames = settings.silence_search_secs as usize * sample_rate as usize;
+
+    let mut ranges = Vec::new();
+    let mut start = 0;
+
+    // Stop once the remainder fits into a single chunk
+    while chunk_frames > 0 && frame_count - start > chunk_frames + search_frames {
+        let mark = start + chunk_frames;
+        let search_start = mark
+            .saturating_sub(search_frames)
+            .max(start + window_frames);
+        let search_end = (mark + search_frames).min(frame_count - window_frames);
+
+        let cut = find_quietest_window(
+            samples,
+            channels,
+            window_frames,
+            search_start..search_end,
+            mark,
+        )
+        .unwrap_or(mark);
+
+        ranges.push(start * channels..cut * channels);
+        start = cut;
+    }
+
+    ranges.push(start * channels..samples.len());
+    ranges
+}
+
+/// Find the start frame of the window with the lowest RMS energy within a frame range
+/// Ties are broken in favour of the window closest to `mark`
+fn find_quietest_window(
+    samples: &[f32],
+    channels: usize,
+    window_frames: usize,
+    frames: Range<usize>,
+    mark: usize,
+) -> Option<usize> {
+    let mut quietest: Option<(usize, f32)> = None;
+
+    for frame in frames.step_by(window_frames) {
+        let window = &samples[frame * channels..(frame + window_frames) * channels];
+        let energy = rms(window);
+
+        let is_better = match quietest {
+            None => true,
+            Some((best_frame, best)) => {
+                energy < best
+                    || (energy == best && frame.abs_diff(mark) < best_frame.abs_diff(mark))
+            }
+        };
+        if is_better {
+            quietest = Some((frame, energy));
+        }
+    }
+
+    quietest.map(|(frame, _)| frame)
+}
+
+/// Root mean square level of a block of samples
+pub fn rms(samples: &[f32]) -> f32 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+    let sum: f32 = samples.iter().map(|s| s * s).sum();
+    (sum / samples.len() as f32).sqrt()
+}
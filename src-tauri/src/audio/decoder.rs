@@ -0,0 +1,227 @@
+//! Audio file decoding (WAV, FLAC, MP3 via symphonia; Ogg-Opus via audiopus)
+
+use std::io::Cursor;
+use std::path::Path;
+
+use audiopus::{coder::Decoder, Channels, SampleRate};
+use symphonia::core::{
+    audio::SampleBuffer, codecs::DecoderOptions, errors::Error as SymphoniaError,
+    formats::FormatOptions, io::MediaSourceStream, meta::MetadataOptions, probe::Hint,
+};
+
+use crate::error::AppError;
+
+use super::encoder;
+
+/// Maximum Opus frame size per channel (120ms at 48kHz)
+const MAX_OPUS_FRAME_SIZE: usize = 5760;
+
+/// Check if the data is an Ogg container with an Opus stream
+fn is_ogg_opus(data: &[u8]) -> bool {
+    data.starts_with(b"OggS") && data.windows(8).take(512).any(|w| w == b"OpusHead")
+}
+
+/// Prepare a decoded audio file for sending to an AI provider
+/// Ogg-Opus files are passed through as-is, everything else is transcoded to Opus
+pub fn encode_file_for_ai(
+    path: &Path,
+    samples: &[f32],
+    sample_rate: u32,
+    channels: u16,
+) -> Result<(Vec<u8>, &'static str), AppError> {
+    let data = std::fs::read(path)
+        .map_err(|e| AppError::Audio(format!("Failed to read audio file {:?}: {}", path, e)))?;
+
+    if is_ogg_opus(&data) {
+        return Ok((data, encoder::opus_mime_type()));
+    }
+
+    encoder::encode_with_fallback(samples, sample_rate, channels)
+}
+
+/// Decode an audio file to interleaved f32 samples
+/// Returns (samples, sample_rate, channels); more than two channels are downmixed to mono
+pub fn decode_file(path: &Path) -> Result<(Vec<f32>, u32, u16), AppError> {
+    let data = std::fs::read(path)
+        .map_err(|e| AppError::Audio(format!("Failed to read audio file {:?}: {}", path, e)))?;
+    let extension = path.extension().and_then(|e| e.to_str());
+
+    let (samples, sample_rate, channels) = decode_bytes(data, extension)?;
+    if samples.is_empty() {
+        return Err(AppError::Audio(format!(
+            "Audio file contains no samples: {:?}",
+            path
+        )));
+    }
+
+    Ok((samples, sample_rate, channels))
+}
+
+/// Decode encoded audio to interleaved f32 samples
+/// `extension` is used as a format hint; more than two channels are downmixed to mono
+pub fn decode_bytes(
+    data: Vec<u8>,
+    extension: Option<&str>,
+) -> Result<(Vec<f32>, u32, u16), AppError> {
+    let (samples, sample_rate, channels) = if is_ogg_opus(&data) {
+        decode_ogg_opus(&data)?
+    } else {
+        decode_with_symphonia(data, extension)?
+    };
+
+    if channels > 2 {
+        return Ok((downmix_to_mono(&samples, channels), sample_rate, 1));
+    }
+
+    Ok((samples, sample_rate, channels))
+}
+
+/// Decode WAV, FLAC or MP3 using symphonia
+fn decode_with_symphonia(
+    data: Vec<u8>,
+    extension: Option<&str>,
+) -> Result<(Vec<f32>, u32, u16), AppError> {
+    let stream = MediaSourceStream::new(Box::new(Cursor::new(data)), Default::default());
+
+    let mut hint = Hint::new();
+    if let Some(ext) = extension {
+        hint.with_extension(ext);
+    }
+
+    let probed = symphonia::default::get_probe()
+        .format(
+            &hint,
+            stream,
+            &FormatOptions::default(),
+            &MetadataOptions::default(),
+        )
+        .map_err(|e| AppError::Audio(format!("Unsupported audio format: {}", e)))?;
+    let mut format = probed.format;
+
+    let track = format
+        .default_track()
+        .ok_or_else(|| AppError::Audio("Audio file has no audio track".to_string()))?;
+    let track_id = track.id;
+    let mut sample_rate = track.codec_params.sample_rate.unwrap_or(0);
+    let mut channels = track
+        .codec_params
+        .channels
+        .map(|c| c.count() as u16)
+        .unwrap_or(0);
+
+    let mut decoder = symphonia::default::get_codecs()
+        .make(&track.codec_params, &DecoderOptions::default())
+        .map_err(|e| AppError::Audio(format!("Unsupported audio codec: {}", e)))?;
+
+    let mut samples = Vec::new();
+
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            // End of stream
+            Err(SymphoniaError::IoError(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+                break
+            }
+            Err(e) => return Err(AppError::Audio(format!("Failed to read audio: {}", e))),
+        };
+
+        if packet.track_id() != track_id {
+            continue;
+        }
+
+        match decoder.decode(&packet) {
+            Ok(decoded) => {
+                let spec = *decoded.spec();
+                sample_rate = spec.rate;
+                channels = spec.channels.count() as u16;
+
+                let mut buffer = SampleBuffer::<f32>::new(decoded.capacity() as u64, spec);
+                buffer.copy_interleaved_ref(decoded);
+                samples.extend_from_slice(buffer.samples());
+            }
+            // Skip corrupted packets
+            Err(SymphoniaError::DecodeError(e)) => {
+                eprintln!("Skipping undecodable audio packet: {}", e);
+            }
+            Err(e) => return Err(AppError::Audio(format!("Failed to decode audio: {}", e))),
+        }
+    }
+
+    if sample_rate == 0 || channels == 0 {
+        return Err(AppError::Audio(
+            "Could not determine audio format".to_string(),
+        ));
+    }
+
+    Ok((samples, sample_rate, channels))
+}
+
+/// Decode an Ogg-Opus stream to 48kHz samples
+fn decode_ogg_opus(data: &[u8]) -> Result<(Vec<f32>, u32, u16), AppError> {
+    use ogg::reading::PacketReader;
+
+    let mut reader = PacketReader::new(Cursor::new(data));
+
+    // First packet: OpusHead identification header
+    let id_header = reader
+        .read_packet()
+        .map_err(|e| AppError::Audio(format!("Failed to read Ogg stream: {}", e)))?
+        .ok_or_else(|| AppError::Audio("Empty Ogg stream".to_string()))?;
+
+    if id_header.data.len() < 19 || !id_header.data.starts_with(b"OpusHead") {
+        return Err(AppError::Audio("Invalid Opus header".to_string()));
+    }
+
+    let channels = id_header.data[9] as u16;
+    let pre_skip = u16::from_le_bytes([id_header.data[10], id_header.data[11]]) as usize;
+
+    let opus_channels = match channels {
+        1 => Channels::Mono,
+        2 => Channels::Stereo,
+        other => {
+            return Err(AppError::Audio(format!(
+                "Unsupported Opus channel count: {}",
+                other
+            )))
+        }
+    };
+
+    let mut decoder = Decoder::new(SampleRate::Hz48000, opus_channels)
+        .map_err(|e| AppError::Audio(format!("Failed to create Opus decoder: {}", e)))?;
+
+    let mut samples = Vec::new();
+    let mut buffer = vec![0f32; MAX_OPUS_FRAME_SIZE * channels as usize];
+    let mut skipped_tags = false;
+
+    while let Some(packet) = reader
+        .read_packet()
+        .map_err(|e| AppError::Audio(format!("Failed to read Ogg stream: {}", e)))?
+    {
+        // Second packet: OpusTags comment header
+        if !skipped_tags {
+            skipped_tags = true;
+            if packet.data.starts_with(b"OpusTags") {
+                continue;
+            }
+        }
+
+        let frame_samples = decoder
+            .decode_float(Some(packet.data.as_slice()), buffer.as_mut_slice(), false)
+            .map_err(|e| AppError::Audio(format!("Opus decoding failed: {}", e)))?;
+        samples.extend_from_slice(&buffer[..frame_samples * channels as usize]);
+    }
+
+    // Drop the encoder priming samples
+    let skip = (pre_skip * channels as usize).min(samples.len());
+    samples.drain(..skip);
+
+    Ok((samples, 48000, channels))
+}
+
+/// Average all channels of interleaved samples into a single channel
+fn downmix_to_mono(samples: &[f32], channels: u16) -> Vec<f32> {
+    samples
+        .chunks(channels as usize)
+        .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32)
+        .collect()
+}
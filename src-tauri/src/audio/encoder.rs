@@ -258,13 +258,33 @@ pub fn encode_to_wav(
     Ok(cursor.into_inner())
 }
 
+/// Encode PCM samples to Opus, falling back to WAV if Opus encoding fails
+/// Returns the encoded data and its MIME type
+pub fn encode_with_fallback(
+    samples: &[f32],
+    sample_rate: u32,
+    channels: u16,
+) -> Result<(Vec<u8>, &'static str), AppError> {
+    match encode_to_opus(samples, sample_rate, channels) {
+        Ok(data) => {
+            eprintln!("Encoded to {} bytes Opus", data.len());
+            Ok((data, opus_mime_type()))
+        }
+        Err(e) => {
+            eprintln!("Opus encoding failed, falling back to WAV: {}", e);
+            let wav_data = encode_to_wav(samples, sample_rate, channels)?;
+            eprintln!("Encoded to {} bytes WAV", wav_data.len());
+            Ok((wav_data, wav_mime_type()))
+        }
+    }
+}
+
 /// Get the MIME type for Opus audio
 pub fn opus_mime_type() -> &'static str {
     "audio/ogg"
 }
 
 /// Get the MIME type for WAV audio
-#[allow(dead_code)]
 pub fn wav_mime_type() -> &'static str {
     "audio/wav"
 }
@@ -1,7 +1,10 @@
 //! Audio recording module
 
+pub mod archive;
+pub mod chunker;
+pub mod decoder;
 pub mod encoder;
 pub mod recorder;
+pub mod service;
 
-pub use encoder::{encode_to_opus, encode_to_wav, opus_mime_type};
-pub use recorder::AudioRecorderHandle;
+pub use encoder::encode_with_fallback;
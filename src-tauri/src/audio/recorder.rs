@@ -1,32 +1,59 @@
 //! Audio recording implementation using cpal
 
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use serde::Serialize;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::mpsc::{self, Sender};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
 use std::sync::{Arc, Mutex};
 use std::thread::{self, JoinHandle};
+use std::time::Duration;
 
 use crate::error::AppError;
 
+use super::chunker::rms;
+
+/// Interval between level reports from the recording thread
+const LEVEL_INTERVAL_MS: u64 = 100;
+
 /// Message sent to the recording thread
 enum RecorderCommand {
     Stop,
 }
 
+/// Periodic input level report from the recording thread
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordingLevel {
+    /// RMS level of the samples since the last report (0.0 - 1.0)
+    pub rms: f32,
+    /// Peak level of the samples since the last report (0.0 - 1.0)
+    pub peak: f32,
+    /// Recorded duration so far
+    pub elapsed_ms: u64,
+    /// Time left until the maximum duration is reached (None if unlimited)
+    pub remaining_ms: Option<u64>,
+    /// Set on the final report once the maximum duration is reached
+    pub limit_reached: bool,
+}
+
 /// Audio recorder that captures from the default input device
 /// Uses a dedicated thread to handle the non-Send stream
 pub struct AudioRecorderHandle {
     command_tx: Sender<RecorderCommand>,
     samples: Arc<Mutex<Vec<f32>>>,
-    is_recording: Arc<AtomicBool>,
+    is_paused: Arc<AtomicBool>,
     sample_rate: u32,
     channels: u16,
     thread_handle: Option<JoinHandle<()>>,
 }
 
 impl AudioRecorderHandle {
-    /// Start a new recording session
-    pub fn start() -> Result<Self, AppError> {
+    /// Start a new recording session that reports input levels every 100ms
+    /// Capturing stops once `max_duration_ms` is reached; the last report has `limit_reached` set.
+    pub fn start_monitored(
+        max_duration_ms: Option<u64>,
+        mut on_level: impl FnMut(RecordingLevel) + Send + 'static,
+    ) -> Result<Self, AppError> {
         let host = cpal::default_host();
         let device = host
             .default_input_device()
@@ -39,12 +66,14 @@ impl AudioRecorderHandle {
         let sample_rate = config.sample_rate().0;
         let channels = config.channels();
         let samples = Arc::new(Mutex::new(Vec::new()));
-        let is_recording = Arc::new(AtomicBool::new(true));
+        let is_paused = Arc::new(AtomicBool::new(false));
 
         let (command_tx, command_rx) = mpsc::channel::<RecorderCommand>();
 
         let samples_clone = Arc::clone(&samples);
-        let is_recording_clone = Arc::clone(&is_recording);
+        // Cleared by the recording thread once the duration limit is reached
+        let is_recording_clone = Arc::new(AtomicBool::new(true));
+        let is_paused_clone = Arc::clone(&is_paused);
         let config_clone = config.clone();
 
         // Spawn a dedicated thread for recording
@@ -64,10 +93,13 @@ impl AudioRecorderHandle {
                 cpal::SampleFormat::F32 => {
                     let samples = Arc::clone(&samples_clone);
                     let is_recording = Arc::clone(&is_recording_clone);
+                    let is_paused = Arc::clone(&is_paused_clone);
                     device.build_input_stream(
                         &config_clone.into(),
                         move |data: &[f32], _: &cpal::InputCallbackInfo| {
-                            if is_recording.load(Ordering::SeqCst) {
+                            if is_recording.load(Ordering::SeqCst)
+                                && !is_paused.load(Ordering::SeqCst)
+                            {
                                 if let Ok(mut s) = samples.lock() {
                                     s.extend_from_slice(data);
                                 }
@@ -80,10 +112,13 @@ impl AudioRecorderHandle {
                 cpal::SampleFormat::I16 => {
                     let samples = Arc::clone(&samples_clone);
                     let is_recording = Arc::clone(&is_recording_clone);
+                    let is_paused = Arc::clone(&is_paused_clone);
                     device.build_input_stream(
                         &config_clone.into(),
                         move |data: &[i16], _: &cpal::InputCallbackInfo| {
-                            if is_recording.load(Ordering::SeqCst) {
+                            if is_recording.load(Ordering::SeqCst)
+                                && !is_paused.load(Ordering::SeqCst)
+                            {
                                 if let Ok(mut s) = samples.lock() {
                                     for &sample in data {
                                         s.push(sample as f32 / 32768.0);
@@ -98,10 +133,13 @@ impl AudioRecorderHandle {
                 cpal::SampleFormat::U16 => {
                     let samples = Arc::clone(&samples_clone);
                     let is_recording = Arc::clone(&is_recording_clone);
+                    let is_paused = Arc::clone(&is_paused_clone);
                     device.build_input_stream(
                         &config_clone.into(),
                         move |data: &[u16], _: &cpal::InputCallbackInfo| {
-                            if is_recording.load(Ordering::SeqCst) {
+                            if is_recording.load(Ordering::SeqCst)
+                                && !is_paused.load(Ordering::SeqCst)
+                            {
                                 if let Ok(mut s) = samples.lock() {
                                     for &sample in data {
                                         s.push((sample as f32 - 32768.0) / 32768.0);
@@ -132,8 +170,44 @@ impl AudioRecorderHandle {
                 return;
             }
 
-            // Wait for stop command or channel close
-            let _ = command_rx.recv();
+            // Report levels until stop command, channel close or duration limit
+            let samples_per_ms = (sample_rate as u64 * channels as u64) / 1000;
+            let mut reported = 0;
+            while let Err(RecvTimeoutError::Timeout) =
+                command_rx.recv_timeout(Duration::from_millis(LEVEL_INTERVAL_MS))
+            {
+                let (rms_level, peak, total) = match samples_clone.lock() {
+                    Ok(s) => {
+                        let new_samples = &s[reported.min(s.len())..];
+                        let peak = new_samples.iter().fold(0f32, |m, v| m.max(v.abs()));
+                        (rms(new_samples), peak, s.len())
+                    }
+                    Err(_) => break,
+                };
+                reported = total;
+
+                let elapsed_ms = total as u64 / samples_per_ms.max(1);
+                let remaining_ms = max_duration_ms.map(|max| max.saturating_sub(elapsed_ms));
+                let limit_reached = remaining_ms == Some(0);
+
+                if limit_reached {
+                    is_recording_clone.store(false, Ordering::SeqCst);
+                }
+
+                on_level(RecordingLevel {
+                    rms: rms_level,
+                    peak,
+                    elapsed_ms,
+                    remaining_ms,
+                    limit_reached,
+                });
+
+                if limit_reached {
+                    // Keep the stream open until stopped, without capturing
+                    let _ = command_rx.recv();
+                    break;
+                }
+            }
 
             // Stream is dropped here, stopping recording
             is_recording_clone.store(false, Ordering::SeqCst);
@@ -142,7 +216,7 @@ impl AudioRecorderHandle {
         Ok(Self {
             command_tx,
             samples,
-            is_recording,
+            is_paused,
             sample_rate,
             channels,
             thread_handle: Some(thread_handle),
@@ -168,8 +242,13 @@ impl AudioRecorderHandle {
         Ok((samples, self.sample_rate, self.channels))
     }
 
-    /// Check if currently recording
-    pub fn is_recording(&self) -> bool {
-        self.is_recording.load(Ordering::SeqCst)
+    /// Pause capturing; the input stream stays open
+    pub fn pause(&self) {
+        self.is_paused.store(true, Ordering::SeqCst);
+    }
+
+    /// Resume capturing after a pause
+    pub fn resume(&self) {
+        self.is_paused.store(false, Ordering::SeqCst);
     }
 }
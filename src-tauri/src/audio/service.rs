@@ -0,0 +1,252 @@
+//! Recording service owning the input device
+//!
+//! Hotkeys and the frontend share a single recording session. The service
+//! tracks who owns the session and reports state changes to the tray and the
+//! frontend (`recording-state` event).
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::sync::Mutex;
+
+use crate::error::AppError;
+use crate::indicator;
+use crate::tray::{self, emit_event, set_icon_state, TrayIconState};
+
+use super::recorder::{AudioRecorderHandle, RecordingLevel};
+
+/// Who started the active recording session
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum RecordingOwner {
+    /// A hotkey with a RecordAudio AI action
+    Hotkey { id: String },
+    /// The settings window
+    Frontend,
+}
+
+/// Recording service state
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RecordingState {
+    Idle,
+    Recording,
+    Paused,
+    /// A finished recording is being sent to the AI provider
+    Processing,
+}
+
+/// Snapshot of the recording service reported to the frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordingStatus {
+    pub state: RecordingState,
+    pub owner: Option<RecordingOwner>,
+}
+
+/// Recorded audio returned when a session is stopped
+pub struct RecordedAudio {
+    pub samples: Vec<f32>,
+    pub sample_rate: u32,
+    pub channels: u16,
+}
+
+struct Session {
+    owner: RecordingOwner,
+    recorder: AudioRecorderHandle,
+    paused: bool,
+}
+
+#[derive(Default)]
+struct ServiceState {
+    session: Option<Session>,
+    /// Number of stopped recordings still being processed
+    processing: usize,
+}
+
+impl ServiceState {
+    fn status(&self) -> RecordingStatus {
+        let state = match &self.session {
+            Some(session) if session.paused => RecordingState::Paused,
+            Some(_) => RecordingState::Recording,
+            None if self.processing > 0 => RecordingState::Processing,
+            None => RecordingState::Idle,
+        };
+
+        RecordingStatus {
+            state,
+            owner: self.session.as_ref().map(|s| s.owner.clone()),
+        }
+    }
+}
+
+static SERVICE: Lazy<Mutex<ServiceState>> = Lazy::new(|| Mutex::new(ServiceState::default()));
+
+fn lock() -> Result<std::sync::MutexGuard<'static, ServiceState>, AppError> {
+    SERVICE
+        .lock()
+        .map_err(|_| AppError::Audio("Failed to lock recording service".to_string()))
+}
+
+/// Get the current recording status
+pub fn status() -> RecordingStatus {
+    lock().map(|s| s.status()).unwrap_or(RecordingStatus {
+        state: RecordingState::Idle,
+        owner: None,
+    })
+}
+
+/// Get the owner of the active session, if any
+pub fn active_owner() -> Option<RecordingOwner> {
+    lock().ok()?.session.as_ref().map(|s| s.owner.clone())
+}
+
+/// Start a recording session
+/// Fails if another session is active. `on_limit` is called from the recording
+/// thread once `max_duration_ms` is reached.
+pub fn start(
+    owner: RecordingOwner,
+    max_duration_ms: Option<u64>,
+    on_limit: impl Fn() + Send + 'static,
+) -> Result<(), AppError> {
+    let mut state = lock()?;
+
+    if let Some(session) = &state.session {
+        return Err(AppError::Audio(
+            match &session.owner {
+                RecordingOwner::Frontend => {
+                    "A recording is already in progress in the settings window"
+                }
+                RecordingOwner::Hotkey { .. } => "A recording is already in progress",
+            }
+            .to_string(),
+        ));
+    }
+
+    let on_level = move |level: RecordingLevel| {
+        let limit_reached = level.limit_reached;
+        emit_event("recording-level", level);
+
+        if limit_reached {
+            eprintln!("Maximum recording duration reached");
+            on_limit();
+        }
+    };
+
+    let recorder = AudioRecorderHandle::start_monitored(max_duration_ms, on_level)?;
+    state.session = Some(Session {
+        owner,
+        recorder,
+        paused: false,
+    });
+    let status = state.status();
+    drop(state);
+
+    indicator::show();
+    report(status);
+    Ok(())
+}
+
+/// Pause the active session
+pub fn pause() -> Result<(), AppError> {
+    set_paused(true)
+}
+
+/// Resume a paused session
+pub fn resume() -> Result<(), AppError> {
+    set_paused(false)
+}
+
+fn set_paused(paused: bool) -> Result<(), AppError> {
+    let mut state = lock()?;
+    let session = state
+        .session
+        .as_mut()
+        .ok_or_else(|| AppError::Audio("No active recording".to_string()))?;
+
+    if session.paused == paused {
+        return Ok(());
+    }
+
+    if paused {
+        session.recorder.pause();
+    } else {
+        session.recorder.resume();
+    }
+    session.paused = paused;
+
+    let status = state.status();
+    drop(state);
+    report(status);
+    Ok(())
+}
+
+/// Stop the active session and return the recorded audio
+/// The service stays in the processing state until `finish_processing` is called.
+pub fn stop() -> Result<RecordedAudio, AppError> {
+    let session = take_session()?;
+
+    {
+        let mut state = lock()?;
+        state.processing += 1;
+    }
+    report(status());
+
+    let (samples, sample_rate, channels) = match session.recorder.stop() {
+        Ok(recorded) => recorded,
+        Err(e) => {
+            finish_processing();
+            return Err(e);
+        }
+    };
+
+    Ok(RecordedAudio {
+        samples,
+        sample_rate,
+        channels,
+    })
+}
+
+/// Mark a stopped recording as fully processed
+pub fn finish_processing() {
+    if let Ok(mut state) = lock() {
+        state.processing = state.processing.saturating_sub(1);
+    }
+    report(status());
+}
+
+/// Stop the active session and throw away the recorded audio
+pub fn discard() -> Result<(), AppError> {
+    let session = take_session()?;
+    report(status());
+
+    session.recorder.stop()?;
+    eprintln!("Recording discarded");
+    Ok(())
+}
+
+/// Remove the active session, hiding the indicator
+fn take_session() -> Result<Session, AppError> {
+    let session = lock()?
+        .session
+        .take()
+        .ok_or_else(|| AppError::Audio("No active recording".to_string()))?;
+
+    indicator::hide();
+    Ok(session)
+}
+
+/// Report a state change to the tray and the frontend
+fn report(status: RecordingStatus) {
+    set_icon_state(match status.state {
+        RecordingState::Idle => TrayIconState::Normal,
+        _ => TrayIconState::Active,
+    });
+    tray::set_tooltip(match status.state {
+        RecordingState::Idle => "Global Hotkey",
+        RecordingState::Recording => "Global Hotkey - Recording",
+        RecordingState::Paused => "Global Hotkey - Recording paused",
+        RecordingState::Processing => "Global Hotkey - Processing recording",
+    });
+    tray::refresh_menu();
+    emit_event("recording-state", status);
+}
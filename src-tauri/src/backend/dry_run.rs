@@ -0,0 +1,443 @@
+//! Dry runs: recording stubs and the timeline they produce
+//!
+//! Reads (clipboard text, config) still hit the real system so templates and
+//! AI inputs resolve as they would in a live run. Writes and launches are only
+//! recorded. Waits advance a simulated clock instead of sleeping.
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Instant;
+
+use chrono::Utc;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+
+use crate::config::schema::{HotkeyConfig, InputStep, Keystroke, MouseButton, ProgramConfig};
+use crate::error::AppError;
+use crate::postaction::clipboard::ClipboardSnapshot;
+use crate::process::spawner::ProgramOutput;
+use crate::process::tracker::{self, TrackedProcess};
+use crate::tray::emit_event;
+
+use super::{AiBackend, Backends, ClipboardBackend, InputBackend, ProcessBackend, PromptBackend};
+
+/// Number of dry runs kept in the history
+const MAX_HISTORY: usize = 20;
+
+/// Longest text quoted in a timeline entry
+const MAX_QUOTED_CHARS: usize = 80;
+
+/// Category of a timeline entry
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TimelineKind {
+    Input,
+    Clipboard,
+    Process,
+    Ai,
+    Prompt,
+    Wait,
+    Notification,
+    /// Step boundaries and other context that is not a side effect
+    Info,
+}
+
+/// Something that would have happened during a dry run
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimelineEntry {
+    /// Simulated time since the start of the run
+    pub at_ms: u64,
+    pub kind: TimelineKind,
+    pub description: String,
+}
+
+/// Result of a dry run of one hotkey
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DryRunReport {
+    pub id: String,
+    pub hotkey_id: String,
+    pub hotkey_name: String,
+    pub started_at: String,
+    /// Simulated duration, including waits that were skipped
+    pub simulated_ms: u64,
+    /// Wall-clock duration of the dry run itself
+    pub duration_ms: u64,
+    pub success: bool,
+    pub error: Option<String>,
+    pub entries: Vec<TimelineEntry>,
+}
+
+#[derive(Default)]
+struct TimelineState {
+    entries: Vec<TimelineEntry>,
+    simulated_ms: u64,
+}
+
+/// Shared recorder for the entries of one dry run
+#[derive(Clone, Default)]
+pub struct Timeline {
+    state: Arc<Mutex<TimelineState>>,
+}
+
+impl Timeline {
+    pub fn record(&self, kind: TimelineKind, description: impl Into<String>) {
+        let mut state = self.state.lock().unwrap();
+        let at_ms = state.simulated_ms;
+        state.entries.push(TimelineEntry {
+            at_ms,
+            kind,
+            description: description.into(),
+        });
+    }
+
+    /// Record a wait and advance the simulated clock
+    pub fn wait(&self, delay_ms: u64) {
+        self.record(TimelineKind::Wait, format!("Wait {} ms", delay_ms));
+        self.state.lock().unwrap().simulated_ms += delay_ms;
+    }
+
+    fn finish(&self) -> (Vec<TimelineEntry>, u64) {
+        let mut state = self.state.lock().unwrap();
+        (std::mem::take(&mut state.entries), state.simulated_ms)
+    }
+}
+
+static HISTORY: Lazy<RwLock<VecDeque<DryRunReport>>> =
+    Lazy::new(|| RwLock::new(VecDeque::with_capacity(MAX_HISTORY)));
+
+/// Run `action` for a hotkey against recording backends
+pub fn run(
+    hotkey: &HotkeyConfig,
+    action: impl FnOnce(&mut Backends) -> Result<(), AppError>,
+) -> DryRunReport {
+    let timeline = Timeline::default();
+    let mut backends = Backends::dry_run(timeline.clone());
+    let started_at = Utc::now();
+    let start = Instant::now();
+
+    let result = action(&mut backends);
+    let (entries, simulated_ms) = timeline.finish();
+
+    DryRunReport {
+        id: uuid::Uuid::new_v4().to_string(),
+        hotkey_id: hotkey.id.clone(),
+        hotkey_name: hotkey.name.clone(),
+        started_at: started_at.to_rfc3339(),
+        simulated_ms,
+        duration_ms: start.elapsed().as_millis() as u64,
+        success: result.is_ok(),
+        error: result.err().map(|e| e.to_string()),
+        entries,
+    }
+}
+
+/// Emit a report to the frontend as `dry-run-report` and add it to the history
+pub fn record(report: DryRunReport) {
+    emit_event("dry-run-report", report.clone());
+
+    let mut history = HISTORY.write().unwrap();
+    if history.len() == MAX_HISTORY {
+        history.pop_back();
+    }
+    history.push_front(report);
+}
+
+/// Get the recorded dry runs, newest first
+pub fn history() -> Vec<DryRunReport> {
+    HISTORY.read().unwrap().iter().cloned().collect()
+}
+
+/// Response returned in place of a real AI call
+pub fn placeholder_response(role_id: &str) -> String {
+    format!("[dry run] response of role '{}'", role_id)
+}
+
+/// Answer used in place of asking the user
+pub fn placeholder_answer(label: &str) -> String {
+    format!("[dry run] {}", label)
+}
+
+/// Quote text for a timeline entry, shortening long text
+fn quote(text: &str) -> String {
+    let mut quoted: String = text.chars().take(MAX_QUOTED_CHARS).collect();
+    if text.chars().count() > MAX_QUOTED_CHARS {
+        quoted.push('…');
+    }
+    format!("{:?}", quoted)
+}
+
+fn describe_keystroke(keystroke: &Keystroke) -> String {
+    let mut parts: Vec<&str> = keystroke.modifiers.iter().map(|s| s.as_str()).collect();
+    parts.push(&keystroke.key);
+    parts.join(" + ")
+}
+
+fn describe_program(program: &ProgramConfig) -> String {
+    let command = std::iter::once(program.path.as_str())
+        .chain(program.arguments.iter().map(|a| a.as_str()))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    match program.timeout_ms {
+        Some(timeout_ms) => format!("{} (timeout {} ms)", command, timeout_ms),
+        None => command,
+    }
+}
+
+fn describe_steps(steps: &[InputStep]) -> String {
+    steps
+        .iter()
+        .map(|step| match step {
+            InputStep::Press { key } => format!("press {}", key),
+            InputStep::Release { key } => format!("release {}", key),
+            InputStep::Tap { key } => format!("tap {}", key),
+            InputStep::Hold { key, duration_ms } => format!("hold {} {} ms", key, duration_ms),
+            InputStep::Repeat { times, steps } => {
+                format!("repeat {}x [{}]", times, describe_steps(steps))
+            }
+            InputStep::TypeUnicode { text } => format!("type {}", quote(text)),
+            InputStep::Wait { delay_ms } => format!("wait {} ms", delay_ms),
+            InputStep::MouseMove { x, y, relative } => {
+                if *relative {
+                    format!("move mouse by ({}, {})", x, y)
+                } else {
+                    format!("move mouse to ({}, {})", x, y)
+                }
+            }
+            InputStep::Click { button } => match button {
+                MouseButton::Left => "left click".to_string(),
+                MouseButton::Middle => "middle click".to_string(),
+                MouseButton::Right => "right click".to_string(),
+            },
+            InputStep::Scroll { amount, horizontal } => {
+                let axis = if *horizontal {
+                    "horizontally"
+                } else {
+                    "vertically"
+                };
+                format!("scroll {} {}", amount, axis)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+pub struct RecordingInput(pub Timeline);
+
+impl InputBackend for RecordingInput {
+    fn paste(&mut self) -> Result<(), AppError> {
+        self.0.record(TimelineKind::Input, "Paste clipboard");
+        Ok(())
+    }
+
+    fn simulate_keystroke(&mut self, keystroke: &Keystroke) -> Result<(), AppError> {
+        self.0.record(
+            TimelineKind::Input,
+            format!("Press {}", describe_keystroke(keystroke)),
+        );
+        Ok(())
+    }
+
+    fn type_text(&mut self, text: &str) -> Result<(), AppError> {
+        self.0
+            .record(TimelineKind::Input, format!("Type {}", quote(text)));
+        Ok(())
+    }
+
+    fn run_script(&mut self, steps: &[InputStep]) -> Result<(), AppError> {
+        self.0.record(
+            TimelineKind::Input,
+            format!("Input script: {}", describe_steps(steps)),
+        );
+        Ok(())
+    }
+}
+
+/// Clipboard stub; text written during the run is returned by later reads
+pub struct RecordingClipboard {
+    timeline: Timeline,
+    contents: Option<String>,
+}
+
+impl RecordingClipboard {
+    pub fn new(timeline: Timeline) -> Self {
+        Self {
+            timeline,
+            contents: None,
+        }
+    }
+}
+
+impl ClipboardBackend for RecordingClipboard {
+    fn get_text(&mut self) -> Result<String, AppError> {
+        match &self.contents {
+            Some(text) => Ok(text.clone()),
+            None => super::live::LiveClipboard.get_text(),
+        }
+    }
+
+    /// Where the selection is the clipboard, earlier writes of the run are seen
+    fn get_primary_selection(&mut self) -> Result<String, AppError> {
+        if cfg!(any(target_os = "windows", target_os = "macos")) {
+            self.get_text()
+        } else {
+            super::live::LiveClipboard.get_primary_selection()
+        }
+    }
+
+    fn set_text(&mut self, text: &str) -> Result<(), AppError> {
+        self.timeline.record(
+            TimelineKind::Clipboard,
+            format!("Set clipboard to {}", quote(text)),
+        );
+        self.contents = Some(text.to_string());
+        Ok(())
+    }
+
+    fn capture(&mut self) -> Result<ClipboardSnapshot, AppError> {
+        self.timeline
+            .record(TimelineKind::Clipboard, "Snapshot clipboard");
+        ClipboardSnapshot::capture()
+    }
+
+    fn restore(&mut self, _snapshot: ClipboardSnapshot) -> Result<(), AppError> {
+        self.timeline
+            .record(TimelineKind::Clipboard, "Restore clipboard snapshot");
+        self.contents = None;
+        Ok(())
+    }
+}
+
+/// Process stub; programs are assumed to succeed without output
+pub struct RecordingProcess(pub Timeline);
+
+impl ProcessBackend for RecordingProcess {
+    fn launch(&mut self, program: &ProgramConfig) -> Result<(), AppError> {
+        self.0.record(
+            TimelineKind::Process,
+            format!("Launch {}", describe_program(program)),
+        );
+        Ok(())
+    }
+
+    fn launch_and_wait(
+        &mut self,
+        program: &ProgramConfig,
+        _capture_output: bool,
+    ) -> Result<ProgramOutput, AppError> {
+        self.0.record(
+            TimelineKind::Process,
+            format!(
+                "Launch {} and wait for exit (assuming exit code 0)",
+                describe_program(program)
+            ),
+        );
+        Ok(ProgramOutput {
+            exit_code: 0,
+            stdout: String::new(),
+            stderr: String::new(),
+            timed_out: false,
+        })
+    }
+
+    /// Assumes the program echoes its input
+    fn launch_with_input(
+        &mut self,
+        program: &ProgramConfig,
+        input: &str,
+        _max_output_bytes: usize,
+    ) -> Result<ProgramOutput, AppError> {
+        self.0.record(
+            TimelineKind::Process,
+            format!(
+                "Pipe {} into {} and wait for exit (assuming it echoes its input)",
+                quote(input),
+                describe_program(program)
+            ),
+        );
+        Ok(ProgramOutput {
+            exit_code: 0,
+            stdout: input.to_string(),
+            stderr: String::new(),
+            timed_out: false,
+        })
+    }
+
+    fn wait_for_match(
+        &mut self,
+        program: &ProgramConfig,
+        regex: Regex,
+    ) -> Result<Option<String>, AppError> {
+        self.0.record(
+            TimelineKind::Process,
+            format!(
+                "Launch {} and wait for output matching /{}/ (assuming a match)",
+                describe_program(program),
+                regex
+            ),
+        );
+        Ok(Some(String::new()))
+    }
+
+    /// The real process table, so toggle hotkeys show what they would kill
+    fn running(&mut self, hotkey_id: &str) -> Vec<TrackedProcess> {
+        tracker::running_for(hotkey_id)
+    }
+
+    fn kill(&mut self, process: &TrackedProcess) -> Result<(), AppError> {
+        self.0.record(
+            TimelineKind::Process,
+            format!("Kill {} (PID {})", process.command, process.pid),
+        );
+        Ok(())
+    }
+
+    fn instances(&mut self, program: &ProgramConfig) -> Vec<u32> {
+        tracker::find_instances(program)
+    }
+
+    fn tracked_instances(&mut self, program: &ProgramConfig) -> Vec<u32> {
+        tracker::find_tracked(program)
+    }
+
+    fn stop(&mut self, program: &ProgramConfig, pid: u32) -> Result<(), AppError> {
+        self.0.record(
+            TimelineKind::Process,
+            format!("Stop running {} (PID {})", program.path, pid),
+        );
+        Ok(())
+    }
+}
+
+/// AI stub; returns a placeholder response
+pub struct RecordingAi(pub Timeline);
+
+impl AiBackend for RecordingAi {
+    fn run_text(&mut self, role_id: &str, text: &str) -> Result<String, AppError> {
+        self.0.record(
+            TimelineKind::Ai,
+            format!(
+                "Run AI role '{}' on {} characters of input",
+                role_id,
+                text.chars().count()
+            ),
+        );
+        Ok(placeholder_response(role_id))
+    }
+}
+
+/// Prompt stub; answers with a placeholder instead of asking
+pub struct RecordingPrompt(pub Timeline);
+
+impl PromptBackend for RecordingPrompt {
+    fn ask(&mut self, label: &str) -> Result<String, AppError> {
+        self.0.record(
+            TimelineKind::Prompt,
+            format!("Ask the user for '{}'", label),
+        );
+        Ok(placeholder_answer(label))
+    }
+}
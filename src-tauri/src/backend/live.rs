@@ -0,0 +1,369 @@
+//! Backends that perform real side effects
+
+use std::io::{self, BufRead, BufReader, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::Duration;
+
+use arboard::Clipboard;
+use regex::Regex;
+
+use crate::ai::runner;
+use crate::config::schema::{HotkeyConfig, InputStep, Keystroke, ProgramConfig, TimeoutOutcome};
+use crate::error::AppError;
+use crate::postaction::clipboard::ClipboardSnapshot;
+use crate::postaction::input::InputSimulator;
+use crate::process::spawner::{self, ProgramOutput};
+use crate::process::tracker::{self, TrackedProcess};
+use crate::process::{logs, script};
+use crate::tray::{send_notification, set_icon_state, TrayIconState};
+
+use super::{AiBackend, ClipboardBackend, InputBackend, ProcessBackend, PromptBackend};
+
+/// Input through enigo; the simulator is created on first use
+#[derive(Default)]
+pub struct LiveInput {
+    simulator: Option<InputSimulator>,
+}
+
+impl LiveInput {
+    fn simulator(&mut self) -> Result<&mut InputSimulator, AppError> {
+        if self.simulator.is_none() {
+            self.simulator = Some(InputSimulator::new()?);
+        }
+        Ok(self.simulator.as_mut().unwrap())
+    }
+}
+
+impl InputBackend for LiveInput {
+    fn paste(&mut self) -> Result<(), AppError> {
+        self.simulator()?.paste()
+    }
+
+    fn simulate_keystroke(&mut self, keystroke: &Keystroke) -> Result<(), AppError> {
+        self.simulator()?.simulate_keystroke(keystroke)
+    }
+
+    fn type_text(&mut self, text: &str) -> Result<(), AppError> {
+        self.simulator()?.type_text(text)
+    }
+
+    fn run_script(&mut self, steps: &[InputStep]) -> Result<(), AppError> {
+        self.simulator()?.run_script(steps)
+    }
+}
+
+/// The system clipboard
+pub struct LiveClipboard;
+
+impl ClipboardBackend for LiveClipboard {
+    fn get_text(&mut self) -> Result<String, AppError> {
+        Clipboard::new()
+            .and_then(|mut clipboard| clipboard.get_text())
+            .map_err(|e| AppError::PostAction(format!("Failed to read clipboard: {}", e)))
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    fn get_primary_selection(&mut self) -> Result<String, AppError> {
+        use arboard::{GetExtLinux, LinuxClipboardKind};
+
+        // An empty selection is not an error
+        match Clipboard::new().and_then(|mut clipboard| {
+            clipboard
+                .get()
+                .clipboard(LinuxClipboardKind::Primary)
+                .text()
+        }) {
+            Ok(text) => Ok(text),
+            Err(arboard::Error::ContentNotAvailable) => Ok(String::new()),
+            Err(e) => Err(AppError::PostAction(format!(
+                "Failed to read primary selection: {}",
+                e
+            ))),
+        }
+    }
+
+    /// Windows and macOS have no primary selection
+    #[cfg(any(target_os = "windows", target_os = "macos"))]
+    fn get_primary_selection(&mut self) -> Result<String, AppError> {
+        self.get_text()
+    }
+
+    fn set_text(&mut self, text: &str) -> Result<(), AppError> {
+        Clipboard::new()
+            .and_then(|mut clipboard| clipboard.set_text(text))
+            .map_err(|e| AppError::PostAction(format!("Failed to set clipboard: {}", e)))
+    }
+
+    fn capture(&mut self) -> Result<ClipboardSnapshot, AppError> {
+        ClipboardSnapshot::capture()
+    }
+
+    fn restore(&mut self, snapshot: ClipboardSnapshot) -> Result<(), AppError> {
+        snapshot.restore()
+    }
+}
+
+/// Real process launches
+/// Detached launches are added to the process table when the hotkey is known.
+#[derive(Default)]
+pub struct LiveProcess {
+    /// ID and name of the hotkey that launches are tracked under
+    owner: Option<(String, String)>,
+}
+
+impl LiveProcess {
+    pub fn for_hotkey(hotkey: &HotkeyConfig) -> Self {
+        Self {
+            owner: Some((hotkey.id.clone(), hotkey.name.clone())),
+        }
+    }
+
+    /// ID and name of the hotkey whose log receives the program's output, if logging is on
+    fn log_owner(&self, program: &ProgramConfig) -> Option<(&str, &str)> {
+        self.owner
+            .as_ref()
+            .filter(|_| program.log_output)
+            .map(|(hotkey_id, hotkey_name)| (hotkey_id.as_str(), hotkey_name.as_str()))
+    }
+}
+
+impl ProcessBackend for LiveProcess {
+    fn launch(&mut self, program: &ProgramConfig) -> Result<(), AppError> {
+        let guard = script::TempFileGuard::new(program);
+        let log = match self.log_owner(program) {
+            Some((hotkey_id, _)) => Some(logs::open(hotkey_id, program)?),
+            None => None,
+        };
+
+        let mut child = spawner::launch_with_output(program, log.is_some())?;
+        guard.release();
+        // Copied through the writer so the log rotates while the program runs
+        let readers = match &log {
+            Some(log) => [child.stdout.take().map(|stdout| log.follow(stdout))]
+                .into_iter()
+                .chain([child.stderr.take().map(|stderr| log.follow(stderr))])
+                .flatten()
+                .collect(),
+            None => Vec::new(),
+        };
+        match &self.owner {
+            Some((hotkey_id, hotkey_name)) => {
+                tracker::track(hotkey_id, hotkey_name, program, child, readers);
+            }
+            // Untracked scripts still need their file removed after exit
+            None => {
+                if let Some(path) = program.temp_file.clone() {
+                    thread::spawn(move || {
+                        let _ = child.wait();
+                        script::remove(&path);
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn launch_and_wait(
+        &mut self,
+        program: &ProgramConfig,
+        capture_output: bool,
+    ) -> Result<ProgramOutput, AppError> {
+        let log_owner = self.log_owner(program);
+        let output = if capture_output || log_owner.is_some() {
+            spawner::launch_and_capture(program)?
+        } else {
+            spawner::launch_and_wait(program)?
+        };
+        if let Some((hotkey_id, hotkey_name)) = log_owner {
+            if let Err(e) = logs::append_output(hotkey_id, program, &output) {
+                eprintln!("Failed to write log for hotkey '{}': {}", hotkey_id, e);
+            }
+
+            // Same report as the tracker gives detached launches
+            if output.exit_code != 0 {
+                let message = format!("'{}' exited with code {}", hotkey_name, output.exit_code);
+                send_notification("Program Failed", &logs::with_tail(hotkey_id, &message));
+            }
+        }
+        Ok(output)
+    }
+
+    fn launch_with_input(
+        &mut self,
+        program: &ProgramConfig,
+        input: &str,
+        max_output_bytes: usize,
+    ) -> Result<ProgramOutput, AppError> {
+        let output = spawner::launch_with_input(program, input, max_output_bytes)?;
+        if let Some((hotkey_id, _)) = self.log_owner(program) {
+            if let Err(e) = logs::append_output(hotkey_id, program, &output) {
+                eprintln!("Failed to write log for hotkey '{}': {}", hotkey_id, e);
+            }
+        }
+        Ok(output)
+    }
+
+    /// The process keeps running after a match; its remaining output is read and discarded,
+    /// or written to the hotkey's log. Launches by a hotkey are tracked like detached ones.
+    fn wait_for_match(
+        &mut self,
+        program: &ProgramConfig,
+        regex: Regex,
+    ) -> Result<Option<String>, AppError> {
+        let Some((hotkey_id, hotkey_name)) = self.owner.clone() else {
+            return wait_for_match_untracked(program, regex);
+        };
+
+        let guard = script::TempFileGuard::new(program);
+        let mut log = match self.log_owner(program) {
+            Some((hotkey_id, _)) => Some(logs::open(hotkey_id, program)?),
+            None => None,
+        };
+
+        let mut child = spawner::launch_piped(program)?;
+        guard.release();
+        let stdout = child.stdout.take();
+        let mut readers = Vec::new();
+        if let Some(mut stderr) = child.stderr.take() {
+            readers.push(match &log {
+                Some(log) => log.follow(stderr),
+                None => thread::spawn(move || {
+                    let _ = io::copy(&mut stderr, &mut io::sink());
+                }),
+            });
+        }
+        let pid = child.id();
+
+        let (tx, rx) = mpsc::channel::<String>();
+        readers.push(thread::spawn(move || {
+            let Some(stdout) = stdout else {
+                return;
+            };
+            let mut matched = false;
+            for line in BufReader::new(stdout).split(b'\n') {
+                let Ok(line) = line else {
+                    break;
+                };
+                if let Some(log) = log.as_mut() {
+                    let _ = log.write_all(&line).and_then(|_| log.write_all(b"\n"));
+                }
+                let text = String::from_utf8_lossy(&line);
+                let text = text.trim_end_matches('\r');
+                if !matched && regex.is_match(text) {
+                    matched = true;
+                    let _ = tx.send(text.to_string());
+                }
+            }
+        }));
+        tracker::track(&hotkey_id, &hotkey_name, program, child, readers);
+
+        // Killing the process closes stdout, which ends the reader below
+        let timed_out = Arc::new(AtomicBool::new(false));
+        if let Some(timeout_ms) = program.timeout_ms {
+            let timed_out = timed_out.clone();
+            thread::spawn(move || {
+                thread::sleep(Duration::from_millis(timeout_ms));
+                // Fails if the process already exited or was killed
+                if tracker::kill(pid).is_ok() {
+                    timed_out.store(true, Ordering::SeqCst);
+                }
+            });
+        }
+
+        // The channel closes without a message if the process exits without a match
+        match rx.recv() {
+            Ok(line) => Ok(Some(line)),
+            Err(_) if timed_out.load(Ordering::SeqCst) => {
+                let timeout_ms = program.timeout_ms.unwrap_or_default();
+                eprintln!(
+                    "Program '{}' timed out after {} ms and was killed",
+                    program.path, timeout_ms
+                );
+                match program.on_timeout {
+                    TimeoutOutcome::Fail => Err(AppError::Process(format!(
+                        "Program '{}' timed out after {} ms and was killed",
+                        program.path, timeout_ms
+                    ))),
+                    TimeoutOutcome::ExitCode { .. } => Ok(None),
+                }
+            }
+            Err(_) => Ok(None),
+        }
+    }
+
+    fn running(&mut self, hotkey_id: &str) -> Vec<TrackedProcess> {
+        tracker::running_for(hotkey_id)
+    }
+
+    fn kill(&mut self, process: &TrackedProcess) -> Result<(), AppError> {
+        tracker::kill(process.pid)
+    }
+
+    fn instances(&mut self, program: &ProgramConfig) -> Vec<u32> {
+        tracker::find_instances(program)
+    }
+
+    fn tracked_instances(&mut self, program: &ProgramConfig) -> Vec<u32> {
+        tracker::find_tracked(program)
+    }
+
+    fn stop(&mut self, _program: &ProgramConfig, pid: u32) -> Result<(), AppError> {
+        tracker::stop(pid);
+        Ok(())
+    }
+}
+
+/// Wait for a line of stdout matching `regex` from a launch that is not tracked
+fn wait_for_match_untracked(
+    program: &ProgramConfig,
+    regex: Regex,
+) -> Result<Option<String>, AppError> {
+    let (tx, rx) = mpsc::channel::<Result<String, AppError>>();
+    let program = program.clone();
+
+    thread::spawn(move || {
+        let mut matched = false;
+        let result = spawner::launch_and_stream(&program, |line| {
+            if !matched && regex.is_match(line) {
+                matched = true;
+                let _ = tx.send(Ok(line.to_string()));
+            }
+        });
+
+        if let Err(e) = result {
+            let _ = tx.send(Err(e));
+        }
+    });
+
+    // The channel closes without a message if the process exits without a match
+    match rx.recv() {
+        Ok(result) => result.map(Some),
+        Err(_) => Ok(None),
+    }
+}
+
+/// Real AI calls; the tray icon shows activity while a call is running
+pub struct LiveAi;
+
+impl AiBackend for LiveAi {
+    fn run_text(&mut self, role_id: &str, text: &str) -> Result<String, AppError> {
+        let config = crate::config::manager::load_config()?;
+
+        set_icon_state(TrayIconState::Active);
+        let result = runner::block_on(runner::run_text(&config.ai, role_id, text));
+        set_icon_state(TrayIconState::Normal);
+
+        result
+    }
+}
+
+/// Prompts in a small dialog window
+pub struct LivePrompt;
+
+impl PromptBackend for LivePrompt {
+    fn ask(&mut self, label: &str) -> Result<String, AppError> {
+        crate::prompt::ask(label)
+    }
+}
@@ -0,0 +1,195 @@
+//! Side-effect boundary for hotkey actions and post-actions
+//!
+//! Everything an action does to the outside world (simulated input, clipboard
+//! writes, process launches, AI calls, prompts, notifications and waits) goes through
+//! [`Backends`]. Live runs use the real implementations; dry runs use recording
+//! stubs that only describe what would have happened.
+
+pub mod dry_run;
+mod live;
+
+use regex::Regex;
+
+use crate::config::schema::{HotkeyConfig, InputStep, InstancePolicy, Keystroke, ProgramConfig};
+use crate::error::AppError;
+use crate::postaction::clipboard::ClipboardSnapshot;
+use crate::process::spawner::ProgramOutput;
+use crate::process::tracker::TrackedProcess;
+
+use self::dry_run::{Timeline, TimelineKind};
+
+/// Keyboard and mouse input
+pub trait InputBackend {
+    /// Simulate a paste (Ctrl+V, Cmd+V on macOS)
+    fn paste(&mut self) -> Result<(), AppError>;
+    fn simulate_keystroke(&mut self, keystroke: &Keystroke) -> Result<(), AppError>;
+    fn type_text(&mut self, text: &str) -> Result<(), AppError>;
+    fn run_script(&mut self, steps: &[InputStep]) -> Result<(), AppError>;
+}
+
+/// Clipboard access
+pub trait ClipboardBackend {
+    fn get_text(&mut self) -> Result<String, AppError>;
+    /// Selected text (X11/Wayland primary selection; the clipboard elsewhere)
+    fn get_primary_selection(&mut self) -> Result<String, AppError>;
+    fn set_text(&mut self, text: &str) -> Result<(), AppError>;
+    fn capture(&mut self) -> Result<ClipboardSnapshot, AppError>;
+    fn restore(&mut self, snapshot: ClipboardSnapshot) -> Result<(), AppError>;
+}
+
+/// Process launches
+pub trait ProcessBackend {
+    /// Launch without waiting
+    fn launch(&mut self, program: &ProgramConfig) -> Result<(), AppError>;
+    /// Launch and wait for exit; output is only captured if `capture_output` is set
+    fn launch_and_wait(
+        &mut self,
+        program: &ProgramConfig,
+        capture_output: bool,
+    ) -> Result<ProgramOutput, AppError>;
+    /// Launch with `input` on stdin and wait for exit, capturing output
+    /// The program is killed once stdout grows past `max_output_bytes`.
+    fn launch_with_input(
+        &mut self,
+        program: &ProgramConfig,
+        input: &str,
+        max_output_bytes: usize,
+    ) -> Result<ProgramOutput, AppError>;
+    /// Launch and wait until a line of stdout matches `regex`
+    /// Returns the matching line, or None if the process exits without a match.
+    fn wait_for_match(
+        &mut self,
+        program: &ProgramConfig,
+        regex: Regex,
+    ) -> Result<Option<String>, AppError>;
+    /// Running programs launched by a hotkey
+    fn running(&mut self, hotkey_id: &str) -> Vec<TrackedProcess>;
+    /// Kill a tracked program and its children
+    fn kill(&mut self, process: &TrackedProcess) -> Result<(), AppError>;
+    /// PIDs of running copies of a program, tracked or not
+    fn instances(&mut self, program: &ProgramConfig) -> Vec<u32>;
+    /// PIDs of running copies of a program that this app launched
+    fn tracked_instances(&mut self, program: &ProgramConfig) -> Vec<u32>;
+    /// Stop a running copy found by `tracked_instances`
+    fn stop(&mut self, program: &ProgramConfig, pid: u32) -> Result<(), AppError>;
+}
+
+/// AI calls
+pub trait AiBackend {
+    /// Run a role on text and return the response
+    fn run_text(&mut self, role_id: &str, text: &str) -> Result<String, AppError>;
+}
+
+/// Questions to the user
+pub trait PromptBackend {
+    /// Ask for a value for `label`; fails if the user cancels
+    fn ask(&mut self, label: &str) -> Result<String, AppError>;
+}
+
+/// The set of backends used by one run
+pub struct Backends {
+    pub input: Box<dyn InputBackend>,
+    pub clipboard: Box<dyn ClipboardBackend>,
+    pub process: Box<dyn ProcessBackend>,
+    pub ai: Box<dyn AiBackend>,
+    pub prompt: Box<dyn PromptBackend>,
+    /// Set for dry runs
+    timeline: Option<Timeline>,
+}
+
+impl Backends {
+    /// Backends that perform real side effects
+    pub fn live() -> Self {
+        Self {
+            input: Box::new(live::LiveInput::default()),
+            clipboard: Box::new(live::LiveClipboard),
+            process: Box::new(live::LiveProcess::default()),
+            ai: Box::new(live::LiveAi),
+            prompt: Box::new(live::LivePrompt),
+            timeline: None,
+        }
+    }
+
+    /// Live backends whose launches are tracked as started by `hotkey`
+    pub fn live_for(hotkey: &HotkeyConfig) -> Self {
+        Self {
+            process: Box::new(live::LiveProcess::for_hotkey(hotkey)),
+            ..Self::live()
+        }
+    }
+
+    /// Recording stubs that add every side effect to `timeline`
+    pub fn dry_run(timeline: Timeline) -> Self {
+        Self {
+            input: Box::new(dry_run::RecordingInput(timeline.clone())),
+            clipboard: Box::new(dry_run::RecordingClipboard::new(timeline.clone())),
+            process: Box::new(dry_run::RecordingProcess(timeline.clone())),
+            ai: Box::new(dry_run::RecordingAi(timeline.clone())),
+            prompt: Box::new(dry_run::RecordingPrompt(timeline.clone())),
+            timeline: Some(timeline),
+        }
+    }
+
+    pub fn is_dry_run(&self) -> bool {
+        self.timeline.is_some()
+    }
+
+    /// Record a free-form timeline entry (no-op for live runs)
+    pub fn note(&self, kind: TimelineKind, description: impl Into<String>) {
+        if let Some(timeline) = &self.timeline {
+            timeline.record(kind, description);
+        }
+    }
+
+    /// Wait before continuing; dry runs only advance the simulated clock
+    pub fn wait(&self, delay_ms: u64) {
+        match &self.timeline {
+            Some(timeline) => timeline.wait(delay_ms),
+            None => std::thread::sleep(std::time::Duration::from_millis(delay_ms)),
+        }
+    }
+
+    /// Apply the program's single-instance policy before launching it
+    /// Returns false if the launch should be skipped.
+    pub fn check_instance_policy(
+        &mut self,
+        program: &ProgramConfig,
+        hotkey_name: &str,
+    ) -> Result<bool, AppError> {
+        match program.instance_policy {
+            InstancePolicy::Always => Ok(true),
+            InstancePolicy::SkipIfRunning => {
+                let pids = self.process.instances(program);
+                let Some(first) = pids.first() else {
+                    return Ok(true);
+                };
+                self.notify(
+                    "Already Running",
+                    &format!(
+                        "'{}' did not start {}: already running (PID {})",
+                        hotkey_name, program.path, first
+                    ),
+                );
+                Ok(false)
+            }
+            // Only our own launches are stopped; an untracked process with the
+            // same executable may be something else entirely (e.g. a shell)
+            InstancePolicy::Restart => {
+                for pid in self.process.tracked_instances(program) {
+                    self.process.stop(program, pid)?;
+                }
+                Ok(true)
+            }
+        }
+    }
+
+    /// Show a notification to the user
+    pub fn notify(&self, title: &str, body: &str) {
+        match &self.timeline {
+            Some(timeline) => {
+                timeline.record(TimelineKind::Notification, format!("{}: {}", title, body))
+            }
+            None => crate::tray::send_notification(title, body),
+        }
+    }
+}
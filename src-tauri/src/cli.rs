@@ -0,0 +1,99 @@
+//! Command-line entry points that run without starting the GUI
+//!
+//! Usage: `global-hotkey --dry-run <hotkey id or name> [--json]`
+
+use crate::backend::dry_run::{DryRunReport, TimelineKind};
+use crate::config;
+use crate::hotkey;
+
+/// Handle command-line arguments
+/// Returns the process exit code if a CLI command ran, or None to start the app.
+/// Unrecognized arguments (e.g. `--minimized` from autostart) start the app.
+pub fn run_cli() -> Option<i32> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let position = args.iter().position(|a| a == "--dry-run")?;
+    let json = args.iter().any(|a| a == "--json");
+    attach_console();
+
+    let Some(target) = args.get(position + 1).filter(|a| !a.starts_with("--")) else {
+        eprintln!("Usage: global-hotkey --dry-run <hotkey id or name> [--json]");
+        return Some(2);
+    };
+
+    // Loading settings resolves a custom config location
+    if let Err(e) = config::manager::load_settings() {
+        eprintln!("Failed to load settings: {}", e);
+        return Some(1);
+    }
+
+    let report = match hotkey::manager::dry_run_configured(target) {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("{}", e);
+            return Some(1);
+        }
+    };
+
+    if json {
+        match serde_json::to_string_pretty(&report) {
+            Ok(text) => println!("{}", text),
+            Err(e) => {
+                eprintln!("Failed to serialize report: {}", e);
+                return Some(1);
+            }
+        }
+    } else {
+        print_timeline(&report);
+    }
+
+    Some(if report.success { 0 } else { 1 })
+}
+
+/// Send output to the console of the shell that started us
+/// Release builds on Windows use the GUI subsystem, which has no console of its
+/// own, so printed output would otherwise be lost. The shell does not wait for
+/// GUI programs, so the output may appear after its next prompt.
+#[cfg(windows)]
+fn attach_console() {
+    use windows::Win32::System::Console::{AttachConsole, ATTACH_PARENT_PROCESS};
+
+    // Fails when there is no parent console (e.g. started from Explorer) or when
+    // a debug build already has one; both are fine
+    let _ = unsafe { AttachConsole(ATTACH_PARENT_PROCESS) };
+}
+
+#[cfg(not(windows))]
+fn attach_console() {}
+
+fn print_timeline(report: &DryRunReport) {
+    println!(
+        "Dry run of '{}' ({} ms simulated)",
+        report.hotkey_name, report.simulated_ms
+    );
+
+    for entry in &report.entries {
+        println!(
+            "{:>8} ms  {:<12}  {}",
+            entry.at_ms,
+            kind_label(entry.kind),
+            entry.description
+        );
+    }
+
+    if let Some(error) = &report.error {
+        println!("Failed: {}", error);
+    }
+}
+
+fn kind_label(kind: TimelineKind) -> &'static str {
+    match kind {
+        TimelineKind::Input => "input",
+        TimelineKind::Clipboard => "clipboard",
+        TimelineKind::Process => "process",
+        TimelineKind::Ai => "ai",
+        TimelineKind::Prompt => "prompt",
+        TimelineKind::Wait => "wait",
+        TimelineKind::Notification => "notification",
+        TimelineKind::Info => "step",
+    }
+}
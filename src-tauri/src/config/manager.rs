@@ -91,6 +91,7 @@ fn migrate_from_legacy() -> Result<bool, AppError> {
         start_with_system: legacy.settings.start_with_system,
         show_tray_notifications: legacy.settings.show_tray_notifications,
         config_location: None, // Use default location
+        ..Default::default()
     };
 
     // Create new config
@@ -193,6 +194,15 @@ pub fn load_settings() -> Result<AppSettings, AppError> {
     Ok(settings)
 }
 
+/// Get the settings last loaded or saved, without reading the file
+/// Falls back to loading them if nothing is cached yet.
+pub fn cached_settings() -> AppSettings {
+    if let Some(settings) = CACHED_SETTINGS.read().unwrap().clone() {
+        return settings;
+    }
+    load_settings().unwrap_or_default()
+}
+
 /// Save settings to file
 pub fn save_settings(settings: &AppSettings) -> Result<(), AppError> {
     let settings_path = get_settings_path()?;
@@ -4,6 +4,9 @@
 //! 1. Settings file (`~/.global-hotkey-settings.json`) - App preferences and config location
 //! 2. Config file (configurable, default `~/.global-hotkey/config.json`) - Hotkeys and AI settings
 
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
 use serde::{Deserialize, Serialize};
 
 /// Application settings stored in a fixed location (~/.global-hotkey-settings.json)
@@ -16,6 +19,20 @@ pub struct AppSettings {
     /// Custom config location. If None, uses default (~/.global-hotkey/)
     #[serde(default)]
     pub config_location: Option<String>,
+    /// Run every hotkey in dry-run mode (record what would happen, without side effects)
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Disable a hotkey that fires more often than this within one second,
+    /// e.g. because its post-actions trigger it again (0 = no limit)
+    #[serde(default = "default_max_triggers_per_second")]
+    pub max_triggers_per_second: u32,
+    /// Terminal emulator for programs that run in a terminal (None = auto-detect)
+    #[serde(default)]
+    pub terminal: Option<String>,
+}
+
+fn default_max_triggers_per_second() -> u32 {
+    10
 }
 
 impl Default for AppSettings {
@@ -24,6 +41,9 @@ impl Default for AppSettings {
             start_with_system: false,
             show_tray_notifications: true,
             config_location: None,
+            dry_run: false,
+            max_triggers_per_second: default_max_triggers_per_second(),
+            terminal: None,
         }
     }
 }
@@ -91,7 +111,23 @@ pub enum HotkeyAction {
         input_source: AiInputSource,
         #[serde(default, rename = "providerId")]
         provider_id: Option<String>,
+        /// Restore the previous clipboard contents once the post-actions have run
+        #[serde(default, rename = "restoreClipboard")]
+        restore_clipboard: Option<ClipboardRestore>,
     },
+    /// Pipe the clipboard text into a program and replace it with the program's stdout
+    FilterClipboard {
+        program: ProgramConfig,
+        /// Largest clipboard text and output accepted, in bytes
+        #[serde(default = "default_filter_max_bytes", rename = "maxBytes")]
+        max_bytes: usize,
+    },
+    /// Run a script stored in the config
+    RunScript { script: ScriptConfig },
+}
+
+fn default_filter_max_bytes() -> usize {
+    1024 * 1024
 }
 
 /// Configuration for a single hotkey
@@ -101,13 +137,20 @@ pub struct HotkeyConfig {
     pub id: String,
     pub name: String,
     pub hotkey: HotkeyBinding,
-    /// The main action to perform - either launch program or call AI
+    /// The main action to perform - launch a program, run a script, call AI or filter the clipboard
     pub action: HotkeyAction,
     pub enabled: bool,
     pub created_at: String,
     pub updated_at: String,
     #[serde(default)]
     pub post_actions: PostActionsConfig,
+    /// Record what the hotkey would do instead of doing it
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Pressing the hotkey while its program is running kills it instead of
+    /// launching another one
+    #[serde(default)]
+    pub toggle: bool,
 }
 
 // Custom deserializer to handle both old format (program field) and new format (action field)
@@ -133,6 +176,10 @@ impl<'de> serde::Deserialize<'de> for HotkeyConfig {
             updated_at: String,
             #[serde(default)]
             post_actions: PostActionsConfig,
+            #[serde(default)]
+            dry_run: bool,
+            #[serde(default)]
+            toggle: bool,
         }
 
         let helper = HotkeyConfigHelper::deserialize(deserializer)?;
@@ -158,6 +205,8 @@ impl<'de> serde::Deserialize<'de> for HotkeyConfig {
             created_at: helper.created_at,
             updated_at: helper.updated_at,
             post_actions: helper.post_actions,
+            dry_run: helper.dry_run,
+            toggle: helper.toggle,
         })
     }
 }
@@ -177,6 +226,169 @@ pub struct ProgramConfig {
     pub arguments: Vec<String>,
     pub working_directory: Option<String>,
     pub hidden: bool,
+    /// Kill the program if it is still running after this many milliseconds
+    /// Only applies when the program is waited on (exit and stdout triggers)
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+    /// How a timed-out program is reported
+    #[serde(default)]
+    pub on_timeout: TimeoutOutcome,
+    /// Start from the app's environment; when off, only `env` and the `.env` file apply
+    #[serde(default = "default_true")]
+    pub inherit_env: bool,
+    /// Optional `.env` file, relative paths resolve against the working directory
+    #[serde(default)]
+    pub env_file: Option<String>,
+    /// Per-variable overrides, applied after the `.env` file
+    #[serde(default)]
+    pub env: BTreeMap<String, EnvValue>,
+    /// What to do when the program is already running
+    #[serde(default)]
+    pub instance_policy: InstancePolicy,
+    /// Write stdout and stderr to `<config_dir>/logs/<hotkey-id>.log`
+    #[serde(default)]
+    pub log_output: bool,
+    /// Launch inside a terminal emulator (Linux, detached launches only)
+    #[serde(default)]
+    pub run_in_terminal: bool,
+    /// Wait for Enter before the terminal closes, so output stays readable
+    #[serde(default)]
+    pub keep_terminal_open: bool,
+    /// Priority and limits for the program (Linux)
+    #[serde(default)]
+    pub resources: ResourceLimits,
+    /// Temporary file the program runs, removed once it has exited
+    #[serde(skip)]
+    pub temp_file: Option<PathBuf>,
+}
+
+/// An inline script run by an interpreter
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptConfig {
+    pub interpreter: ScriptInterpreter,
+    pub body: String,
+    #[serde(default)]
+    pub working_directory: Option<String>,
+    #[serde(default)]
+    pub hidden: bool,
+    /// Kill the script if it is still running after this many milliseconds
+    /// Only applies when the script is waited on (exit and stdout triggers)
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+    /// Per-variable overrides of the app's environment
+    #[serde(default)]
+    pub env: BTreeMap<String, EnvValue>,
+}
+
+/// Interpreters available for inline scripts
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum ScriptInterpreter {
+    Sh,
+    Bash,
+    Python3,
+    Node,
+    Pwsh,
+}
+
+impl ScriptInterpreter {
+    /// Program that runs the script
+    pub fn program(&self) -> &'static str {
+        match self {
+            ScriptInterpreter::Sh => "sh",
+            ScriptInterpreter::Bash => "bash",
+            ScriptInterpreter::Python3 => "python3",
+            ScriptInterpreter::Node => "node",
+            ScriptInterpreter::Pwsh => "pwsh",
+        }
+    }
+
+    /// Extension of the script file; pwsh refuses to run files without `.ps1`
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ScriptInterpreter::Sh | ScriptInterpreter::Bash => "sh",
+            ScriptInterpreter::Python3 => "py",
+            ScriptInterpreter::Node => "js",
+            ScriptInterpreter::Pwsh => "ps1",
+        }
+    }
+}
+
+/// CPU and IO priority, rlimits and systemd scope of a launched program
+/// Only applied on Linux; unset fields leave the app's own settings in place.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceLimits {
+    /// Nice level from -20 (highest priority) to 19 (lowest); negative values need privileges
+    #[serde(default)]
+    pub nice: Option<i32>,
+    /// IO scheduling class
+    #[serde(default)]
+    pub io_priority: Option<IoPriority>,
+    /// Address space limit in MiB (RLIMIT_AS)
+    #[serde(default)]
+    pub memory_mb: Option<u32>,
+    /// CPU time limit in seconds (RLIMIT_CPU)
+    #[serde(default)]
+    pub cpu_seconds: Option<u32>,
+    /// Largest file the program may write, in MiB (RLIMIT_FSIZE)
+    #[serde(default)]
+    pub file_size_mb: Option<u32>,
+    /// Maximum number of open files (RLIMIT_NOFILE)
+    #[serde(default)]
+    pub open_files: Option<u32>,
+    /// Run in a transient systemd user scope, so the program and its children
+    /// are accounted as one unit
+    #[serde(default)]
+    pub systemd_scope: bool,
+}
+
+/// IO scheduling class of a launched program
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum IoPriority {
+    /// Best-effort class with a level from 0 (highest) to 7 (lowest)
+    BestEffort { level: u8 },
+    /// Only gets disk time when no other program needs it
+    Idle,
+}
+
+/// Single-instance policy for launching a program
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum InstancePolicy {
+    /// Always start another copy
+    #[default]
+    Always,
+    /// Don't start the program if it is already running
+    SkipIfRunning,
+    /// Stop running copies, then start the program
+    Restart,
+}
+
+/// Environment override for one variable
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum EnvValue {
+    /// Set the variable; `${VAR}` is expanded from the app's environment
+    Set { value: String },
+    /// Remove the variable
+    Unset,
+    /// Pass the app's value through even when `inherit_env` is off
+    Inherit,
+}
+
+/// Result reported for a program that was killed after its timeout
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum TimeoutOutcome {
+    /// Fail the step with an error that includes the partial output
+    #[default]
+    Fail,
+    /// Report the program as having exited with this code, so exit-based
+    /// triggers (e.g. `onFailure`) decide which post-actions run
+    ExitCode { code: i32 },
 }
 
 /// Trigger timing for post-actions
@@ -191,6 +403,28 @@ pub enum PostActionTrigger {
         #[serde(rename = "delayMs")]
         delay_ms: u64,
     },
+    /// Execute after process exits with any code
+    OnAnyExit,
+    /// Execute after process exits with one of the listed codes
+    OnExitCodes { codes: Vec<i32> },
+    /// Execute after process exits with a non-zero code
+    OnFailure,
+    /// Execute as soon as a line of stdout matches a regular expression
+    /// The process keeps running; actions are skipped if it exits without a match
+    OnStdoutMatch { pattern: String },
+}
+
+impl PostActionTrigger {
+    /// Check if an exit code satisfies an exit-based trigger
+    pub fn matches_exit_code(&self, exit_code: i32) -> bool {
+        match self {
+            PostActionTrigger::OnExit => exit_code == 0,
+            PostActionTrigger::OnAnyExit => true,
+            PostActionTrigger::OnExitCodes { codes } => codes.contains(&exit_code),
+            PostActionTrigger::OnFailure => exit_code != 0,
+            PostActionTrigger::AfterDelay { .. } | PostActionTrigger::OnStdoutMatch { .. } => false,
+        }
+    }
 }
 
 /// Keystroke for simulation
@@ -201,12 +435,29 @@ pub struct Keystroke {
     pub key: String,
 }
 
+/// Restore the clipboard contents from before an action overwrote them
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClipboardRestore {
+    /// Time to wait after the paste before restoring, so the target app has read the clipboard
+    #[serde(default = "default_settle_delay_ms")]
+    pub settle_delay_ms: u64,
+}
+
+fn default_settle_delay_ms() -> u64 {
+    300
+}
+
 /// Types of post-actions
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase", tag = "type")]
 pub enum PostActionType {
     /// Simulate Ctrl+V (Cmd+V on macOS) to paste clipboard
-    PasteClipboard,
+    PasteClipboard {
+        /// Restore the clipboard from before the run after pasting
+        #[serde(default, rename = "restoreClipboard")]
+        restore_clipboard: Option<ClipboardRestore>,
+    },
     /// Simulate a custom keystroke combination
     SimulateKeystroke { keystroke: Keystroke },
     /// Wait for a specified duration before next action
@@ -214,6 +465,103 @@ pub enum PostActionType {
         #[serde(rename = "delayMs")]
         delay_ms: u64,
     },
+    /// Type text, expanding placeholders such as `{clipboard}` and `{date:%Y-%m-%d}`
+    TypeText { text: String },
+    /// Launch another program; when waiting, its exit code is available to later steps
+    RunProgram {
+        program: ProgramConfig,
+        #[serde(default, rename = "waitForExit")]
+        wait_for_exit: bool,
+    },
+    /// Run an AI role and write the response to the clipboard
+    CallAi {
+        #[serde(rename = "roleId")]
+        role_id: String,
+        #[serde(default)]
+        input: AiStepInput,
+    },
+    /// Run a small script of low-level keyboard and mouse input
+    InputScript { steps: Vec<InputStep> },
+}
+
+/// A single step of an input script
+/// Keys use the same names as keystrokes, plus modifier names such as `ctrl`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum InputStep {
+    /// Press a key down without releasing it
+    Press { key: String },
+    /// Release a previously pressed key
+    Release { key: String },
+    /// Press and release a key
+    Tap { key: String },
+    /// Hold a key down for a duration, then release it
+    Hold {
+        key: String,
+        #[serde(rename = "durationMs")]
+        duration_ms: u64,
+    },
+    /// Run a nested sequence of steps several times
+    Repeat { times: u32, steps: Vec<InputStep> },
+    /// Type arbitrary Unicode text verbatim (no placeholder expansion)
+    TypeUnicode { text: String },
+    /// Wait before the next step
+    Wait {
+        #[serde(rename = "delayMs")]
+        delay_ms: u64,
+    },
+    /// Move the mouse cursor to a screen position, or by an offset when relative
+    MouseMove {
+        x: i32,
+        y: i32,
+        #[serde(default)]
+        relative: bool,
+    },
+    /// Click a mouse button
+    Click {
+        #[serde(default)]
+        button: MouseButton,
+    },
+    /// Scroll by a number of steps; positive values scroll down (or right)
+    Scroll {
+        amount: i32,
+        #[serde(default)]
+        horizontal: bool,
+    },
+}
+
+/// Mouse button for input script clicks
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum MouseButton {
+    #[default]
+    Left,
+    Middle,
+    Right,
+}
+
+/// Input for an AI post-action step
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum AiStepInput {
+    /// Current clipboard text
+    #[default]
+    Clipboard,
+    /// Output of the previous step (program stdout or AI response)
+    PreviousOutput,
+}
+
+/// What to do when a post-action step fails
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum OnErrorPolicy {
+    /// Stop the sequence
+    #[default]
+    Abort,
+    /// Record the failure and run the next step
+    Continue,
+    /// Retry the step up to `attempts` more times, then abort
+    Retry { attempts: u32 },
 }
 
 /// A single post-action
@@ -223,6 +571,8 @@ pub struct PostAction {
     pub id: String,
     pub action_type: PostActionType,
     pub enabled: bool,
+    #[serde(default)]
+    pub on_error: OnErrorPolicy,
 }
 
 /// Post-action configuration for a hotkey
@@ -304,6 +654,83 @@ pub struct AiSettings {
     pub default_provider_id: Option<String>,
     #[serde(default)]
     pub roles: Vec<AiRole>,
+    /// Archive of recorded audio for later re-processing
+    #[serde(default)]
+    pub archive: RecordingArchiveSettings,
+    /// Splitting of long recordings into sequentially transcribed chunks
+    #[serde(default)]
+    pub chunking: ChunkingSettings,
+}
+
+/// Retention settings for the recording archive
+/// Recordings are stored in `<config_dir>/recordings/` when enabled
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordingArchiveSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Maximum total size of the archive in megabytes (0 = unlimited)
+    #[serde(default = "default_archive_max_size_mb")]
+    pub max_size_mb: u64,
+    /// Maximum age of archived recordings in days (0 = keep forever)
+    #[serde(default = "default_archive_max_age_days")]
+    pub max_age_days: u64,
+}
+
+impl Default for RecordingArchiveSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_size_mb: default_archive_max_size_mb(),
+            max_age_days: default_archive_max_age_days(),
+        }
+    }
+}
+
+fn default_archive_max_size_mb() -> u64 {
+    500
+}
+
+fn default_archive_max_age_days() -> u64 {
+    30
+}
+
+/// Settings for splitting long recordings into chunks
+/// Gemini limits the inline audio size, and long requests time out
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChunkingSettings {
+    /// Recordings longer than this are split (seconds, 0 = never split)
+    #[serde(default = "default_chunk_threshold_secs")]
+    pub threshold_secs: u64,
+    /// Target length of each chunk (seconds)
+    #[serde(default = "default_chunk_length_secs")]
+    pub chunk_length_secs: u64,
+    /// How far around each chunk mark to search for silence (seconds)
+    #[serde(default = "default_silence_search_secs")]
+    pub silence_search_secs: u64,
+}
+
+impl Default for ChunkingSettings {
+    fn default() -> Self {
+        Self {
+            threshold_secs: default_chunk_threshold_secs(),
+            chunk_length_secs: default_chunk_length_secs(),
+            silence_search_secs: default_silence_search_secs(),
+        }
+    }
+}
+
+fn default_chunk_threshold_secs() -> u64 {
+    360 // 6 minutes
+}
+
+fn default_chunk_length_secs() -> u64 {
+    300 // 5 minutes
+}
+
+fn default_silence_search_secs() -> u64 {
+    15
 }
 
 /// Audio format for recording
@@ -327,6 +754,12 @@ pub enum AiInputSource {
         format: AudioFormat,
     },
     ProcessOutput,
+    /// Audio file (WAV, Ogg-Opus, FLAC or MP3) from a configured path,
+    /// or the first file on the clipboard if no path is set
+    AudioFile {
+        #[serde(default)]
+        path: Option<String>,
+    },
 }
 
 fn default_max_duration() -> u64 {
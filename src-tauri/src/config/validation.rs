@@ -1,8 +1,11 @@
 //! Configuration validation
 
 use crate::error::AppError;
+use crate::process::env;
 
-use super::schema::{AppConfig, HotkeyAction};
+use super::schema::{
+    AppConfig, HotkeyAction, HotkeyConfig, IoPriority, PostActionType, ProgramConfig,
+};
 
 /// Validate the main configuration
 pub fn validate_config(config: &AppConfig) -> Result<(), AppError> {
@@ -19,8 +22,22 @@ pub fn validate_config(config: &AppConfig) -> Result<(), AppError> {
     Ok(())
 }
 
+/// Reject post-action keystrokes that would trigger the hotkey again,
+/// directly or through other registered hotkeys
+/// Checked on registration, since loops depend on which hotkeys are registered.
+pub fn validate_trigger_loops(hotkey: &HotkeyConfig) -> Result<(), AppError> {
+    match crate::hotkey::conflict::find_trigger_loop(hotkey) {
+        Some(chain) => Err(AppError::Config(format!(
+            "Post-action keystrokes of '{}' would trigger it again and loop forever: {}",
+            hotkey.name,
+            chain.join(" → ")
+        ))),
+        None => Ok(()),
+    }
+}
+
 /// Validate a single hotkey configuration
-fn validate_hotkey(hotkey: &super::schema::HotkeyConfig) -> Result<(), AppError> {
+fn validate_hotkey(hotkey: &HotkeyConfig) -> Result<(), AppError> {
     // Check ID
     if hotkey.id.is_empty() {
         return Err(AppError::Config("Hotkey ID cannot be empty".into()));
@@ -38,10 +55,22 @@ fn validate_hotkey(hotkey: &super::schema::HotkeyConfig) -> Result<(), AppError>
 
     // Validate action based on type
     match &hotkey.action {
-        HotkeyAction::LaunchProgram { program } => {
+        HotkeyAction::LaunchProgram { program } | HotkeyAction::FilterClipboard { program, .. } => {
             if program.path.is_empty() {
                 return Err(AppError::Config("Program path cannot be empty".into()));
             }
+            validate_program(program)?;
+        }
+        HotkeyAction::RunScript { script } => {
+            if script.body.trim().is_empty() {
+                return Err(AppError::Config("Script cannot be empty".into()));
+            }
+            if let Some(name) = script.env.keys().find(|name| !env::is_valid_name(name)) {
+                return Err(AppError::Config(format!(
+                    "Invalid environment variable name '{}'",
+                    name
+                )));
+            }
         }
         HotkeyAction::CallAi { role_id, .. } => {
             if role_id.is_empty() {
@@ -50,5 +79,38 @@ fn validate_hotkey(hotkey: &super::schema::HotkeyConfig) -> Result<(), AppError>
         }
     }
 
+    for action in &hotkey.post_actions.actions {
+        if let PostActionType::RunProgram { program, .. } = &action.action_type {
+            validate_program(program)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Validate the environment and resource options of a program
+fn validate_program(program: &ProgramConfig) -> Result<(), AppError> {
+    if let Some(name) = program.env.keys().find(|name| !env::is_valid_name(name)) {
+        return Err(AppError::Config(format!(
+            "Invalid environment variable name '{}'",
+            name
+        )));
+    }
+    if program
+        .resources
+        .nice
+        .is_some_and(|nice| !(-20..=19).contains(&nice))
+    {
+        return Err(AppError::Config(
+            "Nice level must be between -20 and 19".into(),
+        ));
+    }
+    if let Some(IoPriority::BestEffort { level }) = program.resources.io_priority {
+        if level > 7 {
+            return Err(AppError::Config(
+                "IO priority level must be between 0 and 7".into(),
+            ));
+        }
+    }
     Ok(())
 }
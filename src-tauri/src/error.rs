@@ -31,6 +31,9 @@ pub enum AppError {
 
     #[error("Audio error: {0}")]
     Audio(String),
+
+    #[error("Prompt error: {0}")]
+    Prompt(String),
 }
 
 impl From<AppError> for String {
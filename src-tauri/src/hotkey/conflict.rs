@@ -1,6 +1,8 @@
 //! Hotkey conflict detection
 
-use crate::config::schema::HotkeyBinding;
+use std::collections::HashSet;
+
+use crate::config::schema::{HotkeyBinding, HotkeyConfig, PostActionType};
 
 use super::manager::REGISTRY;
 
@@ -29,6 +31,77 @@ pub fn check_conflict_excluding(binding: &HotkeyBinding, exclude_id: Option<&str
     false
 }
 
+/// Find a chain of registered hotkeys through which `config`'s post-action
+/// keystrokes would trigger `config` again
+/// Returns the hotkey names along the loop, starting and ending with `config`.
+pub fn find_trigger_loop(config: &HotkeyConfig) -> Option<Vec<String>> {
+    let registry = REGISTRY.read().unwrap();
+
+    // The registered hotkeys, with `config` replacing its own (possibly stale) entry
+    let mut hotkeys: Vec<&HotkeyConfig> = registry
+        .iter()
+        .filter(|(id, _)| **id != config.id)
+        .map(|(_, (_, _, hotkey))| hotkey)
+        .collect();
+    hotkeys.push(config);
+
+    let mut path = vec![config.name.clone()];
+    let mut visited = HashSet::new();
+    find_path_back(config, &config.id, &hotkeys, &mut path, &mut visited).then_some(path)
+}
+
+/// Depth-first search for a path of triggered hotkeys leading back to `target_id`
+fn find_path_back(
+    current: &HotkeyConfig,
+    target_id: &str,
+    hotkeys: &[&HotkeyConfig],
+    path: &mut Vec<String>,
+    visited: &mut HashSet<String>,
+) -> bool {
+    for binding in sent_bindings(current) {
+        for next in hotkeys
+            .iter()
+            .filter(|h| bindings_match(&h.hotkey, &binding))
+        {
+            if next.id == target_id {
+                path.push(next.name.clone());
+                return true;
+            }
+
+            if visited.insert(next.id.clone()) {
+                path.push(next.name.clone());
+                if find_path_back(next, target_id, hotkeys, path, visited) {
+                    return true;
+                }
+                path.pop();
+            }
+        }
+    }
+
+    false
+}
+
+/// Bindings sent by a hotkey's enabled keystroke post-actions
+fn sent_bindings(config: &HotkeyConfig) -> Vec<HotkeyBinding> {
+    if !config.post_actions.enabled {
+        return Vec::new();
+    }
+
+    config
+        .post_actions
+        .actions
+        .iter()
+        .filter(|action| action.enabled)
+        .filter_map(|action| match &action.action_type {
+            PostActionType::SimulateKeystroke { keystroke } => Some(HotkeyBinding {
+                modifiers: keystroke.modifiers.clone(),
+                key: keystroke.key.clone(),
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
 /// Check if two hotkey bindings are equivalent
 fn bindings_match(a: &HotkeyBinding, b: &HotkeyBinding) -> bool {
     // Keys must match (case-insensitive)
@@ -1,6 +1,7 @@
 //! Hotkey registration and management
 
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::RwLock;
 
 use arboard::Clipboard;
@@ -10,16 +11,22 @@ use global_hotkey::{
 };
 use once_cell::sync::Lazy;
 
-use crate::ai::{AiProvider, GeminiProvider};
+use crate::ai::runner;
 use crate::audio;
-use crate::config::schema::{AiInputSource, HotkeyAction, HotkeyBinding, HotkeyConfig};
+use crate::audio::service::{self as recording_service, RecordingOwner};
+use crate::backend::dry_run::{self, DryRunReport, TimelineKind};
+use crate::backend::Backends;
+use crate::config::schema::{
+    AiInputSource, HotkeyAction, HotkeyBinding, HotkeyConfig, ProgramConfig,
+};
+use crate::config::validation;
 use crate::error::AppError;
+use crate::postaction;
 use crate::process;
+use crate::template::{self, TemplateContext};
 use crate::tray::{send_notification, set_icon_state, TrayIconState};
 
-/// Track active audio recordings by hotkey ID
-static ACTIVE_RECORDINGS: Lazy<RwLock<HashMap<String, audio::AudioRecorderHandle>>> =
-    Lazy::new(|| RwLock::new(HashMap::new()));
+use super::rate_limit::{self, Decision};
 
 /// Registry entry containing hotkey ID, HotKey object, and configuration
 type RegistryEntry = (u32, HotKey, HotkeyConfig);
@@ -81,112 +88,508 @@ fn handle_event(event: GlobalHotKeyEvent) {
         return;
     }
 
+    let Some((config_id, config)) = REGISTRY
+        .read()
+        .unwrap()
+        .iter()
+        .find(|(_, (hotkey_id, _, _))| *hotkey_id == event.id)
+        .map(|(id, (_, _, config))| (id.clone(), config.clone()))
+    else {
+        return;
+    };
+
+    let max_per_second = crate::config::manager::cached_settings().max_triggers_per_second;
+
+    match rate_limit::record_trigger(&config_id, max_per_second) {
+        Decision::Allow => execute_hotkey(config_id, &config),
+        Decision::Trip => disable_runaway_hotkey(config_id, config.name, max_per_second),
+        Decision::Block => {}
+    }
+}
+
+/// Disable a hotkey that tripped the trigger-rate breaker
+/// It is unregistered, saved as disabled and the user is notified.
+fn disable_runaway_hotkey(id: String, name: String, max_per_second: u32) {
+    eprintln!(
+        "Hotkey '{}' fired more than {} times per second, disabling it",
+        name, max_per_second
+    );
+
+    if let Err(e) = save_disabled(&id) {
+        eprintln!("Failed to save disabled hotkey '{}': {}", name, e);
+    }
+
+    // Unregistering must happen on the main thread
+    let app_handle = crate::tray::APP_HANDLE.read().unwrap().clone();
+    if let Some(app) = app_handle {
+        let unregister_id = id.clone();
+        let _ = app.run_on_main_thread(move || {
+            if let Err(e) = unregister(&unregister_id) {
+                eprintln!("Failed to unregister runaway hotkey: {}", e);
+            }
+        });
+    }
+
+    crate::tray::refresh_menu();
+    crate::tray::emit_event(
+        "hotkey-disabled",
+        serde_json::json!({ "id": id, "name": name }),
+    );
+    send_notification(
+        "Hotkey Disabled",
+        &format!(
+            "'{}' fired more than {} times per second, possibly triggering itself. Re-enable it in Settings.",
+            name, max_per_second
+        ),
+    );
+}
+
+/// Mark a hotkey as disabled in the saved configuration
+fn save_disabled(id: &str) -> Result<(), AppError> {
+    let mut config = crate::config::manager::load_config()?;
+    if let Some(hotkey) = config.hotkeys.iter_mut().find(|h| h.id == id) {
+        hotkey.enabled = false;
+        crate::config::manager::save_config(&config)?;
+    }
+    Ok(())
+}
+
+/// Trigger a registered hotkey's action as if it had been pressed
+pub fn trigger(id: &str) {
     let registry = REGISTRY.read().unwrap();
-    for (config_id, (hotkey_id, _, config)) in registry.iter() {
-        if *hotkey_id == event.id {
-            let action = config.action.clone();
-            let post_actions = config.post_actions.clone();
-            let hotkey_name = config.name.clone();
-            let config_id = config_id.clone();
-
-            // Spawn in a separate thread to avoid blocking the event loop
-            std::thread::spawn(move || {
-                match action {
-                    HotkeyAction::LaunchProgram { program } => {
-                        // Check if post-actions are enabled
-                        if post_actions.enabled && !post_actions.actions.is_empty() {
-                            if let Err(e) = crate::postaction::execute_with_post_actions(
-                                &program,
-                                &post_actions,
-                                &hotkey_name,
-                            ) {
-                                eprintln!(
-                                    "Failed to execute hotkey '{}' with post-actions: {}",
-                                    hotkey_name, e
-                                );
-                            }
-                        } else {
-                            // No post-actions, just launch normally
-                            if let Err(e) = process::spawner::launch(&program) {
-                                eprintln!(
-                                    "Failed to launch program for hotkey '{}': {}",
-                                    hotkey_name, e
-                                );
-                            }
-                        }
+    if let Some((_, _, config)) = registry.get(id) {
+        execute_hotkey(id.to_string(), config);
+    }
+}
+
+/// Execute a hotkey's action and post-actions on a separate thread
+fn execute_hotkey(config_id: String, config: &HotkeyConfig) {
+    let config = config.clone();
+
+    // Spawn in a separate thread to avoid blocking the event loop
+    std::thread::spawn(move || {
+        if is_dry_run(&config) {
+            let report = dry_run(&config);
+            send_notification(
+                "Dry Run",
+                &format!(
+                    "'{}' recorded {} events{}",
+                    config.name,
+                    report.entries.len(),
+                    if report.success { "" } else { " (failed)" }
+                ),
+            );
+            dry_run::record(report);
+            return;
+        }
+
+        let _ = run_action(&config_id, &config, &mut Backends::live_for(&config));
+    });
+}
+
+/// Check if a hotkey runs in dry-run mode, either on its own or globally
+fn is_dry_run(config: &HotkeyConfig) -> bool {
+    config.dry_run || crate::config::manager::cached_settings().dry_run
+}
+
+/// Run a hotkey's action and post-actions against recording backends
+/// Nothing is typed, written to the clipboard, launched or sent to an AI provider.
+/// Audio recording hotkeys are simulated as a complete start-stop cycle.
+fn dry_run(config: &HotkeyConfig) -> DryRunReport {
+    dry_run::run(config, |backends| run_action(&config.id, config, backends))
+}
+
+/// Dry-run a configured hotkey, looked up by ID or (case-insensitive) name
+/// Works for disabled hotkeys too. The report is added to the dry-run history.
+pub fn dry_run_configured(id_or_name: &str) -> Result<DryRunReport, AppError> {
+    let config = crate::config::manager::load_config()?;
+    let hotkey = config
+        .hotkeys
+        .iter()
+        .find(|h| h.id == id_or_name)
+        .or_else(|| {
+            config
+                .hotkeys
+                .iter()
+                .find(|h| h.name.eq_ignore_ascii_case(id_or_name))
+        })
+        .ok_or_else(|| AppError::Hotkey(format!("No hotkey with ID or name '{}'", id_or_name)))?;
+
+    let report = dry_run(hotkey);
+    dry_run::record(report.clone());
+    Ok(report)
+}
+
+/// Run a hotkey's action and post-actions on the current thread
+fn run_action(
+    config_id: &str,
+    config: &HotkeyConfig,
+    backends: &mut Backends,
+) -> Result<(), AppError> {
+    let hotkey_name = &config.name;
+    let post_actions = &config.post_actions;
+
+    match &config.action {
+        HotkeyAction::LaunchProgram { program } => {
+            // Toggle hotkeys stop their running program instead of starting another
+            if config.toggle {
+                let running = backends.process.running(config_id);
+                if !running.is_empty() {
+                    for process in &running {
+                        backends.process.kill(process)?;
                     }
-                    HotkeyAction::CallAi {
-                        role_id,
-                        input_source,
-                        provider_id,
-                    } => {
-                        match execute_ai_action(&config_id, &role_id, &input_source, &provider_id) {
-                            Ok(completed) => {
-                                // Only execute post-actions if action actually completed
-                                // (not just started recording)
-                                if completed
-                                    && post_actions.enabled
-                                    && !post_actions.actions.is_empty()
-                                {
-                                    if let Err(e) =
-                                        crate::postaction::execute_post_actions(&post_actions)
-                                    {
-                                        eprintln!(
-                                            "Failed to execute post-actions for hotkey '{}': {}",
-                                            hotkey_name, e
-                                        );
-                                    }
-                                }
-                            }
-                            Err(e) => {
-                                eprintln!(
-                                    "Failed to execute AI action for hotkey '{}': {}",
-                                    hotkey_name, e
-                                );
-                            }
-                        }
+                    backends.notify(
+                        "Program Stopped",
+                        &format!(
+                            "'{}' stopped {} running program(s)",
+                            hotkey_name,
+                            running.len()
+                        ),
+                    );
+                    return Ok(());
+                }
+            }
+
+            if !backends.check_instance_policy(program, hotkey_name)? {
+                return Ok(());
+            }
+
+            let context = TemplateContext {
+                hotkey_name: Some(hotkey_name.clone()),
+                ..Default::default()
+            };
+            let program =
+                &template::expand_program(program, &context, backends).inspect_err(|e| {
+                    eprintln!(
+                        "Failed to expand arguments for hotkey '{}': {}",
+                        hotkey_name, e
+                    )
+                })?;
+
+            // Check if post-actions are enabled
+            if post_actions.enabled && !post_actions.actions.is_empty() {
+                postaction::execute_with_post_actions(program, post_actions, hotkey_name, backends)
+                    .inspect_err(|e| {
+                        eprintln!(
+                            "Failed to execute hotkey '{}' with post-actions: {}",
+                            hotkey_name, e
+                        )
+                    })
+            } else {
+                // No post-actions, just launch normally
+                backends.process.launch(program).inspect_err(|e| {
+                    eprintln!(
+                        "Failed to launch program for hotkey '{}': {}",
+                        hotkey_name, e
+                    )
+                })
+            }
+        }
+        HotkeyAction::CallAi {
+            role_id,
+            input_source,
+            provider_id,
+            restore_clipboard,
+        } => {
+            // Snapshot before the AI response overwrites the clipboard
+            let clipboard = if restore_clipboard.is_some()
+                || postaction::needs_clipboard_snapshot(post_actions)
+            {
+                backends
+                    .clipboard
+                    .capture()
+                    .map_err(|e| eprintln!("Failed to snapshot clipboard: {}", e))
+                    .ok()
+            } else {
+                None
+            };
+
+            match execute_ai_action(config_id, role_id, input_source, provider_id, backends) {
+                Ok(Some(response)) => {
+                    // Only execute post-actions if action actually completed
+                    // (not just started recording). Without post-actions the
+                    // response stays on the clipboard.
+                    if post_actions.enabled && !post_actions.actions.is_empty() {
+                        postaction::execute_post_actions(
+                            post_actions,
+                            hotkey_name,
+                            Some(response),
+                            clipboard,
+                            restore_clipboard.as_ref(),
+                            backends,
+                        )
+                        .inspect_err(|e| {
+                            eprintln!(
+                                "Failed to execute post-actions for hotkey '{}': {}",
+                                hotkey_name, e
+                            )
+                        })
+                    } else {
+                        Ok(())
                     }
                 }
-            });
-            break;
+                Ok(None) => Ok(()),
+                Err(e) => {
+                    eprintln!(
+                        "Failed to execute AI action for hotkey '{}': {}",
+                        hotkey_name, e
+                    );
+                    Err(e)
+                }
+            }
+        }
+        HotkeyAction::RunScript { script } => {
+            // Dry runs describe the script instead of writing it to disk
+            let program = if backends.is_dry_run() {
+                backends.note(
+                    TimelineKind::Process,
+                    format!(
+                        "Write a {}-line {} script to a temp file",
+                        script.body.lines().count(),
+                        script.interpreter.program()
+                    ),
+                );
+                process::script::program(script, Path::new("<script>"))
+            } else {
+                process::script::prepare(script)?
+            };
+
+            if post_actions.enabled && !post_actions.actions.is_empty() {
+                postaction::execute_with_post_actions(&program, post_actions, hotkey_name, backends)
+                    .inspect_err(|e| {
+                        eprintln!(
+                            "Failed to execute hotkey '{}' with post-actions: {}",
+                            hotkey_name, e
+                        )
+                    })
+            } else {
+                backends.process.launch(&program).inspect_err(|e| {
+                    eprintln!("Failed to run script for hotkey '{}': {}", hotkey_name, e)
+                })
+            }
+        }
+        HotkeyAction::FilterClipboard { program, max_bytes } => {
+            let context = TemplateContext {
+                hotkey_name: Some(hotkey_name.clone()),
+                ..Default::default()
+            };
+            let program = &template::expand_program(program, &context, backends)?;
+
+            let clipboard = if postaction::needs_clipboard_snapshot(post_actions) {
+                backends
+                    .clipboard
+                    .capture()
+                    .map_err(|e| eprintln!("Failed to snapshot clipboard: {}", e))
+                    .ok()
+            } else {
+                None
+            };
+
+            match execute_filter_action(program, *max_bytes, backends) {
+                Ok(output) => {
+                    backends.notify("Clipboard Filtered", "Output saved to clipboard");
+                    postaction::execute_post_actions(
+                        post_actions,
+                        hotkey_name,
+                        Some(output),
+                        clipboard,
+                        None,
+                        backends,
+                    )
+                    .inspect_err(|e| {
+                        eprintln!(
+                            "Failed to execute post-actions for hotkey '{}': {}",
+                            hotkey_name, e
+                        )
+                    })
+                }
+                Err(e) => {
+                    eprintln!(
+                        "Failed to filter clipboard for hotkey '{}': {}",
+                        hotkey_name, e
+                    );
+                    backends.notify("Filter Failed", &format!("'{}': {}", hotkey_name, e));
+                    Err(e)
+                }
+            }
         }
     }
 }
 
-/// Result of an AI action - indicates if it completed (true) or just started (false)
-type AiActionCompleted = bool;
+/// Time limit for filter programs without a timeout of their own
+const FILTER_TIMEOUT_MS: u64 = 10_000;
+
+/// Lines of stderr quoted when a filter program fails
+const FILTER_STDERR_LINES: usize = 5;
+
+/// Pipe the clipboard text through a program and put its stdout on the clipboard
+/// The clipboard is only replaced if the program exits with code 0.
+fn execute_filter_action(
+    program: &ProgramConfig,
+    max_bytes: usize,
+    backends: &mut Backends,
+) -> Result<String, AppError> {
+    let input = backends.clipboard.get_text()?;
+    if input.len() > max_bytes {
+        return Err(AppError::Process(format!(
+            "Clipboard text is too large to filter ({} bytes, limit {})",
+            input.len(),
+            max_bytes
+        )));
+    }
+
+    let program = ProgramConfig {
+        timeout_ms: program.timeout_ms.or(Some(FILTER_TIMEOUT_MS)),
+        ..program.clone()
+    };
+    let output = backends
+        .process
+        .launch_with_input(&program, &input, max_bytes)?;
+
+    // Checked first: a filter that printed too much was killed, so its exit code is meaningless
+    if output.stdout.len() > max_bytes {
+        return Err(AppError::Process(format!(
+            "Filter output is too large (over {} bytes)",
+            max_bytes
+        )));
+    }
+
+    if output.timed_out || output.exit_code != 0 {
+        let reason = if output.timed_out {
+            "timed out".to_string()
+        } else {
+            format!("exited with code {}", output.exit_code)
+        };
+        let stderr: Vec<&str> = output.stderr.trim().lines().collect();
+        let tail = stderr[stderr.len().saturating_sub(FILTER_STDERR_LINES)..].join("\n");
+        return Err(AppError::Process(if tail.is_empty() {
+            format!("'{}' {}", program.path, reason)
+        } else {
+            format!("'{}' {}:\n{}", program.path, reason, tail)
+        }));
+    }
+
+    // Most tools end their output with a newline; keep it only if the input had one
+    let mut text = output.stdout;
+    if !input.ends_with('\n') && text.ends_with('\n') {
+        text.pop();
+        if text.ends_with('\r') {
+            text.pop();
+        }
+    }
+
+    backends.clipboard.set_text(&text)?;
+    Ok(text)
+}
+
+/// Result of an AI action - the response if it completed, None if it just started
+type AiActionResponse = Option<String>;
 
 /// Execute an AI action
-/// Returns Ok(true) if the action completed, Ok(false) if it just started (e.g., recording)
+/// Returns Ok(Some(response)) if the action completed, Ok(None) if it just started (e.g., recording)
 fn execute_ai_action(
     hotkey_id: &str,
     role_id: &str,
     input_source: &AiInputSource,
     _provider_id: &Option<String>,
-) -> Result<AiActionCompleted, AppError> {
-    match input_source {
-        AiInputSource::Clipboard => {
-            execute_clipboard_ai_action(role_id)?;
-            Ok(true) // Completed
+    backends: &mut Backends,
+) -> Result<AiActionResponse, AppError> {
+    if backends.is_dry_run() {
+        if let Some(source) = describe_audio_source(input_source) {
+            return dry_run_audio_ai_action(role_id, &source, backends).map(Some);
         }
-        AiInputSource::RecordAudio { .. } => execute_audio_ai_action(hotkey_id, role_id),
+    }
+
+    match input_source {
+        AiInputSource::Clipboard => execute_clipboard_ai_action(role_id, backends).map(Some),
+        AiInputSource::RecordAudio {
+            max_duration_ms, ..
+        } => execute_audio_ai_action(hotkey_id, role_id, *max_duration_ms),
         AiInputSource::ProcessOutput => Err(AppError::Ai(
             "Process output not yet implemented".to_string(),
         )),
+        AiInputSource::AudioFile { path } => {
+            execute_audio_file_ai_action(role_id, path.as_deref()).map(Some)
+        }
     }
 }
 
 /// Execute AI action with clipboard input
-fn execute_clipboard_ai_action(role_id: &str) -> Result<(), AppError> {
-    // Set icon to active state
+/// The AI backend shows the active tray icon while the call runs
+fn execute_clipboard_ai_action(role_id: &str, backends: &mut Backends) -> Result<String, AppError> {
+    let result = execute_clipboard_ai_action_inner(role_id, backends);
+
+    // Send notification on completion
+    match &result {
+        Ok(_) => {
+            backends.notify("AI Complete", "Response saved to clipboard");
+        }
+        Err(e) => {
+            backends.notify("AI Error", &e.to_string());
+        }
+    }
+
+    result
+}
+
+fn execute_clipboard_ai_action_inner(
+    role_id: &str,
+    backends: &mut Backends,
+) -> Result<String, AppError> {
+    let text = backends.clipboard.get_text()?;
+    let response = backends.ai.run_text(role_id, &text)?;
+    backends.clipboard.set_text(&response)?;
+
+    eprintln!("AI action completed, response saved to clipboard");
+    Ok(response)
+}
+
+/// Describe the audio an AI action would use, or None for non-audio sources
+fn describe_audio_source(input_source: &AiInputSource) -> Option<String> {
+    match input_source {
+        AiInputSource::RecordAudio {
+            max_duration_ms, ..
+        } if *max_duration_ms > 0 => Some(format!(
+            "audio recorded until the hotkey is pressed again (max {} ms)",
+            max_duration_ms
+        )),
+        AiInputSource::RecordAudio { .. } => {
+            Some("audio recorded until the hotkey is pressed again".to_string())
+        }
+        AiInputSource::AudioFile { path: Some(path) } if !path.trim().is_empty() => {
+            Some(format!("audio file {}", path))
+        }
+        AiInputSource::AudioFile { .. } => Some("the audio file on the clipboard".to_string()),
+        AiInputSource::Clipboard | AiInputSource::ProcessOutput => None,
+    }
+}
+
+/// Stand-in for audio AI actions in dry runs
+/// The microphone is never opened and files are never decoded.
+fn dry_run_audio_ai_action(
+    role_id: &str,
+    source: &str,
+    backends: &mut Backends,
+) -> Result<String, AppError> {
+    backends.note(
+        TimelineKind::Ai,
+        format!("Run AI role '{}' on {}", role_id, source),
+    );
+    let response = dry_run::placeholder_response(role_id);
+    backends.clipboard.set_text(&response)?;
+    backends.notify("AI Complete", "Response saved to clipboard");
+
+    Ok(response)
+}
+
+/// Execute AI action with an audio file as input
+fn execute_audio_file_ai_action(role_id: &str, path: Option<&str>) -> Result<String, AppError> {
     set_icon_state(TrayIconState::Active);
 
-    let result = execute_clipboard_ai_action_inner(role_id);
+    let result = execute_audio_file_ai_action_inner(role_id, path);
 
-    // Reset icon to normal state
     set_icon_state(TrayIconState::Normal);
 
-    // Send notification on completion
     match &result {
         Ok(_) => {
             send_notification("AI Complete", "Response saved to clipboard");
@@ -199,69 +602,103 @@ fn execute_clipboard_ai_action(role_id: &str) -> Result<(), AppError> {
     result
 }
 
-fn execute_clipboard_ai_action_inner(role_id: &str) -> Result<(), AppError> {
+fn execute_audio_file_ai_action_inner(
+    role_id: &str,
+    path: Option<&str>,
+) -> Result<String, AppError> {
     let config = crate::config::manager::load_config()?;
-    let ai_settings = &config.ai;
-
-    let provider = ai_settings
-        .providers
-        .first()
-        .ok_or_else(|| AppError::Ai("No AI provider configured".to_string()))?;
-
-    let builtin_roles = crate::ai::get_builtin_roles();
-    let role = ai_settings
-        .roles
-        .iter()
-        .find(|r| r.id == role_id)
-        .or_else(|| builtin_roles.iter().find(|r| r.id == role_id))
-        .ok_or_else(|| AppError::Ai(format!("Role not found: {}", role_id)))?;
-
-    let gemini = GeminiProvider::new(provider.api_key.clone(), provider.model.clone());
-
-    let rt = tokio::runtime::Runtime::new()
-        .map_err(|e| AppError::Ai(format!("Failed to create runtime: {}", e)))?;
 
     let mut clipboard =
         Clipboard::new().map_err(|e| AppError::Ai(format!("Clipboard error: {}", e)))?;
-    let text = clipboard
-        .get_text()
-        .map_err(|e| AppError::Ai(format!("Failed to read clipboard: {}", e)))?;
 
-    let response = rt.block_on(gemini.send_text(&role.system_prompt, &text))?;
+    let file_path = match path.filter(|p| !p.trim().is_empty()) {
+        Some(p) => PathBuf::from(p),
+        None => audio_file_from_clipboard(&mut clipboard)?,
+    };
+
+    eprintln!("Loading audio file {:?}...", file_path);
+    let (samples, sample_rate, channels) = audio::decoder::decode_file(&file_path)?;
+
+    let response = if audio::chunker::needs_chunking(
+        samples.len(),
+        sample_rate,
+        channels,
+        &config.ai.chunking,
+    ) {
+        runner::block_on(runner::run_long_audio(
+            &config.ai,
+            role_id,
+            &samples,
+            sample_rate,
+            channels,
+            notify_chunk_progress,
+        ))?
+    } else {
+        let (audio_data, mime_type) =
+            audio::decoder::encode_file_for_ai(&file_path, &samples, sample_rate, channels)?;
+
+        eprintln!("Sending audio file to AI...");
+        runner::block_on(runner::run_audio(
+            &config.ai,
+            role_id,
+            &audio_data,
+            mime_type,
+        ))?
+    };
 
     clipboard
-        .set_text(&response.text)
+        .set_text(&response)
         .map_err(|e| AppError::Ai(format!("Failed to set clipboard: {}", e)))?;
 
-    eprintln!("AI action completed, response saved to clipboard");
-    Ok(())
+    eprintln!("Audio file AI action completed, response saved to clipboard");
+    Ok(response)
 }
 
-/// Execute AI action with audio recording (toggle behavior)
-/// First press: start recording (returns Ok(false))
-/// Second press: stop recording, process with AI, save to clipboard (returns Ok(true))
-fn execute_audio_ai_action(hotkey_id: &str, role_id: &str) -> Result<AiActionCompleted, AppError> {
-    // Check if there's an active recording for this hotkey
-    let has_active_recording = {
-        let recordings = ACTIVE_RECORDINGS.read().unwrap();
-        recordings.contains_key(hotkey_id)
-    };
+/// Get an audio file path from the clipboard (copied file, or a path as text)
+fn audio_file_from_clipboard(clipboard: &mut Clipboard) -> Result<PathBuf, AppError> {
+    if let Ok(files) = clipboard.get().file_list() {
+        if let Some(file) = files.into_iter().next() {
+            return Ok(file);
+        }
+    }
+
+    let text = clipboard
+        .get_text()
+        .map_err(|_| AppError::Ai("No audio file on the clipboard".to_string()))?;
+    let trimmed = text.trim();
+    let path = PathBuf::from(trimmed.strip_prefix("file://").unwrap_or(trimmed));
 
-    if has_active_recording {
-        // Stop recording and process
-        eprintln!("Stopping audio recording...");
+    if path.is_file() {
+        Ok(path)
+    } else {
+        Err(AppError::Ai(format!(
+            "Clipboard does not contain an audio file path: {}",
+            trimmed
+        )))
+    }
+}
 
-        let recorder = {
-            let mut recordings = ACTIVE_RECORDINGS.write().unwrap();
-            recordings.remove(hotkey_id)
-        };
+/// Execute AI action with audio recording (toggle behavior)
+/// First press: start recording (returns Ok(None))
+/// Second press: stop recording, process with AI, save to clipboard (returns the response)
+fn execute_audio_ai_action(
+    hotkey_id: &str,
+    role_id: &str,
+    max_duration_ms: u64,
+) -> Result<AiActionResponse, AppError> {
+    let owner = RecordingOwner::Hotkey {
+        id: hotkey_id.to_string(),
+    };
 
-        if let Some(recorder) = recorder {
-            // Keep icon active during processing
-            let result = process_audio_recording(recorder, role_id);
+    match recording_service::active_owner() {
+        Some(active) if active == owner => {
+            // Stop recording and process
+            eprintln!("Stopping audio recording...");
+            let recorded = recording_service::stop()?;
 
-            // Reset icon to normal state
-            set_icon_state(TrayIconState::Normal);
+            // Service stays in the processing state until the AI call finishes
+            let result = process_audio_recording(recorded, role_id);
+            recording_service::finish_processing();
 
             // Send notification on completion
             match &result {
@@ -273,37 +710,43 @@ fn execute_audio_ai_action(hotkey_id: &str, role_id: &str) -> Result<AiActionCom
                 }
             }
 
-            result?;
-            return Ok(true); // Action completed
+            result.map(Some) // Action completed
         }
-    } else {
-        // Start recording - set icon to active
-        eprintln!("Starting audio recording...");
-        set_icon_state(TrayIconState::Active);
-
-        match audio::AudioRecorderHandle::start() {
-            Ok(recorder) => {
-                let mut recordings = ACTIVE_RECORDINGS.write().unwrap();
-                recordings.insert(hotkey_id.to_string(), recorder);
-                eprintln!("Recording started. Press hotkey again to stop and process.");
-            }
-            Err(e) => {
-                set_icon_state(TrayIconState::Normal);
+        Some(_) => {
+            let e = AppError::Audio("Another recording is already in progress".to_string());
+            send_notification("Recording Error", &e.to_string());
+            Err(e)
+        }
+        None => {
+            eprintln!("Starting audio recording...");
+
+            // Stop and process automatically once the maximum duration is reached
+            let limit_hotkey_id = hotkey_id.to_string();
+            let max_duration = (max_duration_ms > 0).then_some(max_duration_ms);
+
+            if let Err(e) =
+                recording_service::start(owner, max_duration, move || trigger(&limit_hotkey_id))
+            {
                 send_notification("Recording Error", &e.to_string());
                 return Err(e);
             }
+
+            eprintln!("Recording started. Press hotkey again to stop and process.");
+            Ok(None) // Just started recording, not completed
         }
     }
-
-    Ok(false) // Just started recording, not completed
 }
 
 /// Process recorded audio: encode, send to AI, save response
 fn process_audio_recording(
-    recorder: audio::AudioRecorderHandle,
+    recorded: recording_service::RecordedAudio,
     role_id: &str,
-) -> Result<(), AppError> {
-    let (samples, sample_rate, channels) = recorder.stop()?;
+) -> Result<String, AppError> {
+    let recording_service::RecordedAudio {
+        samples,
+        sample_rate,
+        channels,
+    } = recorded;
 
     if samples.is_empty() {
         return Err(AppError::Audio("No audio recorded".to_string()));
@@ -312,60 +755,77 @@ fn process_audio_recording(
     eprintln!("Recorded {} samples at {} Hz", samples.len(), sample_rate);
 
     // Encode to Opus (default, ~10x smaller than WAV)
-    let (audio_data, mime_type) = match audio::encode_to_opus(&samples, sample_rate, channels) {
-        Ok(data) => {
-            eprintln!("Encoded to {} bytes Opus", data.len());
-            (data, audio::opus_mime_type())
-        }
-        Err(e) => {
-            // Fallback to WAV if Opus fails
-            eprintln!("Opus encoding failed, falling back to WAV: {}", e);
-            let wav_data = audio::encode_to_wav(&samples, sample_rate, channels)?;
-            eprintln!("Encoded to {} bytes WAV", wav_data.len());
-            (wav_data, "audio/wav")
-        }
-    };
+    let (audio_data, mime_type) = audio::encode_with_fallback(&samples, sample_rate, channels)?;
 
-    // Load config and find role
     let config = crate::config::manager::load_config()?;
-    let ai_settings = &config.ai;
-
-    let provider = ai_settings
-        .providers
-        .first()
-        .ok_or_else(|| AppError::Ai("No AI provider configured".to_string()))?;
 
-    let builtin_roles = crate::ai::get_builtin_roles();
-    let role = ai_settings
-        .roles
-        .iter()
-        .find(|r| r.id == role_id)
-        .or_else(|| builtin_roles.iter().find(|r| r.id == role_id))
-        .ok_or_else(|| AppError::Ai(format!("Role not found: {}", role_id)))?;
+    // Archive before sending so the recording survives a failed AI call
+    let archive_id =
+        match audio::archive::store(&config.ai.archive, &audio_data, mime_type, role_id) {
+            Ok(id) => id,
+            Err(e) => {
+                eprintln!("Failed to archive recording: {}", e);
+                None
+            }
+        };
 
-    let gemini = GeminiProvider::new(provider.api_key.clone(), provider.model.clone());
+    let result = if audio::chunker::needs_chunking(
+        samples.len(),
+        sample_rate,
+        channels,
+        &config.ai.chunking,
+    ) {
+        runner::block_on(runner::run_long_audio(
+            &config.ai,
+            role_id,
+            &samples,
+            sample_rate,
+            channels,
+            notify_chunk_progress,
+        ))
+    } else {
+        eprintln!("Sending audio to AI...");
+        runner::block_on(runner::run_audio(
+            &config.ai,
+            role_id,
+            &audio_data,
+            mime_type,
+        ))
+    };
 
-    let rt = tokio::runtime::Runtime::new()
-        .map_err(|e| AppError::Ai(format!("Failed to create runtime: {}", e)))?;
+    if let Some(id) = archive_id {
+        if let Err(e) = audio::archive::record_result(&id, role_id, &result) {
+            eprintln!("Failed to update archived recording: {}", e);
+        }
+    }
 
-    eprintln!("Sending audio to AI...");
-    let response = rt.block_on(gemini.send_audio(&role.system_prompt, &audio_data, mime_type))?;
+    let response = result?;
 
     // Save response to clipboard
     let mut clipboard =
         Clipboard::new().map_err(|e| AppError::Ai(format!("Clipboard error: {}", e)))?;
     clipboard
-        .set_text(&response.text)
+        .set_text(&response)
         .map_err(|e| AppError::Ai(format!("Failed to set clipboard: {}", e)))?;
 
     eprintln!("Audio AI action completed, response saved to clipboard");
-    Ok(())
+    Ok(response)
+}
+
+/// Notify the user about progress through a chunked transcription
+fn notify_chunk_progress(index: usize, count: usize) {
+    eprintln!("Transcribing chunk {} of {}...", index + 1, count);
+    send_notification(
+        "Transcribing",
+        &format!("Processing part {} of {}...", index + 1, count),
+    );
 }
 
 /// Register a hotkey - must be called from the main thread
 pub fn register(config: &HotkeyConfig) -> Result<(), AppError> {
     // Parse the hotkey first
     let hotkey = parse_hotkey(&config.hotkey)?;
+    validation::validate_trigger_loops(config)?;
     let hotkey_id = hotkey.id();
 
     MANAGER.with(|m| {
@@ -387,9 +847,10 @@ pub fn register(config: &HotkeyConfig) -> Result<(), AppError> {
         Ok::<(), AppError>(())
     })?;
 
-    // Store in registry
+    // Store in registry; re-registering clears a tripped rate limit
     let mut registry = REGISTRY.write().unwrap();
     registry.insert(config.id.clone(), (hotkey_id, hotkey, config.clone()));
+    rate_limit::reset(&config.id);
 
     Ok(())
 }
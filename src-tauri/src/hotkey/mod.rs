@@ -3,3 +3,4 @@
 pub mod conflict;
 pub mod handler;
 pub mod manager;
+mod rate_limit;
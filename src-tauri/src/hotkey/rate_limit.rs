@@ -0,0 +1,73 @@
+//! Trigger-rate circuit breaker against runaway hotkeys
+//!
+//! A hotkey whose post-actions end up triggering it again fires in a tight
+//! loop. Once a hotkey exceeds the configured rate it trips the breaker and
+//! stays blocked until it is registered again.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use once_cell::sync::Lazy;
+
+/// Window over which triggers are counted
+const WINDOW: Duration = Duration::from_secs(1);
+
+#[derive(Default)]
+struct BreakerState {
+    /// Recent trigger times per hotkey ID
+    triggers: HashMap<String, VecDeque<Instant>>,
+    /// Hotkeys that tripped the breaker
+    tripped: HashSet<String>,
+}
+
+static STATE: Lazy<Mutex<BreakerState>> = Lazy::new(|| Mutex::new(BreakerState::default()));
+
+/// What to do with a trigger
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Decision {
+    /// Run the hotkey
+    Allow,
+    /// The hotkey just exceeded the limit; disable it
+    Trip,
+    /// The hotkey already tripped; ignore the trigger
+    Block,
+}
+
+/// Record a trigger of a hotkey and decide whether to run it
+/// `max_per_second` of 0 disables the limit.
+pub fn record_trigger(id: &str, max_per_second: u32) -> Decision {
+    let mut state = STATE.lock().unwrap();
+
+    if state.tripped.contains(id) {
+        return Decision::Block;
+    }
+    if max_per_second == 0 {
+        return Decision::Allow;
+    }
+
+    let now = Instant::now();
+    let triggers = state.triggers.entry(id.to_string()).or_default();
+    while triggers
+        .front()
+        .is_some_and(|t| now.duration_since(*t) > WINDOW)
+    {
+        triggers.pop_front();
+    }
+    triggers.push_back(now);
+
+    if triggers.len() > max_per_second as usize {
+        state.triggers.remove(id);
+        state.tripped.insert(id.to_string());
+        Decision::Trip
+    } else {
+        Decision::Allow
+    }
+}
+
+/// Forget a hotkey's triggers and clear a tripped breaker
+pub fn reset(id: &str) {
+    let mut state = STATE.lock().unwrap();
+    state.triggers.remove(id);
+    state.tripped.remove(id);
+}
@@ -0,0 +1,57 @@
+//! Always-on-top recording indicator window
+//!
+//! The window renders the frontend with the `#recording-indicator` route and
+//! subscribes to `recording-level` events emitted while recording.
+
+use tauri::{Manager, WebviewUrl, WebviewWindowBuilder};
+
+use crate::tray::APP_HANDLE;
+
+const INDICATOR_LABEL: &str = "recording-indicator";
+
+/// Show the recording indicator window (creates it on first use)
+pub fn show() {
+    let app_handle = {
+        let handle = APP_HANDLE.read().unwrap();
+        handle.clone()
+    };
+
+    let Some(app) = app_handle else {
+        return;
+    };
+
+    if let Some(window) = app.get_webview_window(INDICATOR_LABEL) {
+        let _ = window.show();
+        return;
+    }
+
+    let result = WebviewWindowBuilder::new(
+        &app,
+        INDICATOR_LABEL,
+        WebviewUrl::App("index.html#recording-indicator".into()),
+    )
+    .title("Recording")
+    .inner_size(240.0, 72.0)
+    .resizable(false)
+    .decorations(false)
+    .always_on_top(true)
+    .skip_taskbar(true)
+    .focused(false)
+    .build();
+
+    if let Err(e) = result {
+        eprintln!("Failed to create recording indicator: {}", e);
+    }
+}
+
+/// Hide the recording indicator window
+pub fn hide() {
+    let app_handle = {
+        let handle = APP_HANDLE.read().unwrap();
+        handle.clone()
+    };
+
+    if let Some(window) = app_handle.and_then(|app| app.get_webview_window(INDICATOR_LABEL)) {
+        let _ = window.hide();
+    }
+}
@@ -4,20 +4,19 @@
 
 mod ai;
 mod audio;
+mod backend;
+mod cli;
 mod config;
 mod error;
 mod hotkey;
+mod indicator;
 mod postaction;
 mod process;
+mod prompt;
+mod template;
 mod tray;
 
-use once_cell::sync::Lazy;
-use std::sync::Mutex;
-
-// Global audio recorder state
-static AUDIO_RECORDER: Lazy<Mutex<Option<audio::AudioRecorderHandle>>> =
-    Lazy::new(|| Mutex::new(None));
-
+pub use cli::run_cli;
 pub use config::schema::{
     AppConfig, AppSettings, FullConfig, HotkeyAction, HotkeyBinding, HotkeyConfig, ProgramConfig,
 };
@@ -149,7 +148,36 @@ async fn get_registered_hotkeys() -> Vec<String> {
 /// Launch a program with the given configuration
 #[tauri::command]
 async fn launch_program(config: ProgramConfig) -> Result<(), String> {
-    process::spawner::launch(&config).map_err(|e| e.to_string())
+    // Launchers keep empty arguments, so drop the unused ones here
+    let config = ProgramConfig {
+        arguments: config
+            .arguments
+            .into_iter()
+            .filter(|a| !a.is_empty())
+            .collect(),
+        ..config
+    };
+    process::spawner::launch(&config)
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+/// Get the programs launched by hotkeys, oldest first
+#[tauri::command]
+async fn get_processes() -> Vec<process::tracker::TrackedProcess> {
+    process::tracker::list()
+}
+
+/// Kill a running program launched by a hotkey, together with its children
+#[tauri::command]
+async fn kill_process(pid: u32) -> Result<(), String> {
+    process::tracker::kill(pid).map_err(|e| e.to_string())
+}
+
+/// Get the end of a hotkey's program log
+#[tauri::command]
+async fn get_program_log(hotkey_id: String) -> Result<String, String> {
+    process::logs::read(&hotkey_id).map_err(|e| e.to_string())
 }
 
 /// Validate that a program path exists and is executable
@@ -164,6 +192,12 @@ async fn get_executable_extensions() -> Vec<&'static str> {
     process::spawner::get_executable_extensions()
 }
 
+/// List installed applications from the XDG application directories
+#[tauri::command]
+async fn list_applications() -> Vec<process::desktop::DesktopApp> {
+    process::desktop::list_applications()
+}
+
 // ============================================================================
 // Tauri Commands - System Tray
 // ============================================================================
@@ -280,17 +314,11 @@ async fn delete_ai_role(role_id: String) -> Result<(), String> {
 // Tauri Commands - Audio Recording
 // ============================================================================
 
-/// Start audio recording
+/// Start audio recording from the settings window
 #[tauri::command]
 async fn start_audio_recording() -> Result<(), String> {
-    let recorder = audio::AudioRecorderHandle::start().map_err(|e| e.to_string())?;
-
-    let mut guard = AUDIO_RECORDER
-        .lock()
-        .map_err(|e| format!("Failed to lock recorder: {}", e))?;
-    *guard = Some(recorder);
-
-    Ok(())
+    audio::service::start(audio::service::RecordingOwner::Frontend, None, || {})
+        .map_err(|e| e.to_string())
 }
 
 /// Audio recording result with data and mime type
@@ -300,49 +328,76 @@ struct AudioRecordingResult {
     data: String,
     /// MIME type (audio/ogg for Opus, audio/wav for fallback)
     mime_type: String,
+    /// ID in the recording archive, if archiving is enabled
+    archive_id: Option<String>,
 }
 
 /// Stop audio recording and return audio data as base64 with mime type
 /// Uses Opus encoding by default (with WAV fallback)
 #[tauri::command]
 async fn stop_audio_recording() -> Result<AudioRecordingResult, String> {
-    let mut guard = AUDIO_RECORDER
-        .lock()
-        .map_err(|e| format!("Failed to lock recorder: {}", e))?;
-
-    let recorder = guard
-        .take()
-        .ok_or_else(|| "No active recording".to_string())?;
+    if audio::service::active_owner() != Some(audio::service::RecordingOwner::Frontend) {
+        return Err("No active recording".to_string());
+    }
 
-    let (samples, sample_rate, channels) = recorder.stop().map_err(|e| e.to_string())?;
+    let recorded = audio::service::stop().map_err(|e| e.to_string())?;
 
     // Try Opus encoding first (smaller files), fall back to WAV
-    let (audio_data, mime_type) = match audio::encode_to_opus(&samples, sample_rate, channels) {
-        Ok(data) => (data, audio::opus_mime_type()),
-        Err(e) => {
-            eprintln!("Opus encoding failed, falling back to WAV: {}", e);
-            let wav_data =
-                audio::encode_to_wav(&samples, sample_rate, channels).map_err(|e| e.to_string())?;
-            (wav_data, "audio/wav")
-        }
-    };
+    let encoded =
+        audio::encode_with_fallback(&recorded.samples, recorded.sample_rate, recorded.channels);
+
+    // The frontend sends the audio itself, so processing ends here
+    audio::service::finish_processing();
+    let (audio_data, mime_type) = encoded.map_err(|e| e.to_string())?;
+
+    // Archived like hotkey recordings; the AI call is made by the frontend,
+    // so no role is recorded yet
+    let archive_settings = config::manager::load_config()
+        .map(|c| c.ai.archive)
+        .unwrap_or_default();
+    let archive_id = audio::archive::store(&archive_settings, &audio_data, mime_type, "")
+        .unwrap_or_else(|e| {
+            eprintln!("Failed to archive recording: {}", e);
+            None
+        });
 
     // Return as base64 for easy transfer to frontend
     use base64::Engine;
     Ok(AudioRecordingResult {
         data: base64::engine::general_purpose::STANDARD.encode(&audio_data),
         mime_type: mime_type.to_string(),
+        archive_id,
     })
 }
 
-/// Check if currently recording
+/// Check if currently recording (including paused)
 #[tauri::command]
 async fn is_audio_recording() -> Result<bool, String> {
-    let guard = AUDIO_RECORDER
-        .lock()
-        .map_err(|e| format!("Failed to lock recorder: {}", e))?;
+    Ok(audio::service::active_owner().is_some())
+}
 
-    Ok(guard.as_ref().map(|r| r.is_recording()).unwrap_or(false))
+/// Get the recording service state
+#[tauri::command]
+async fn get_recording_status() -> audio::service::RecordingStatus {
+    audio::service::status()
+}
+
+/// Pause the active recording
+#[tauri::command]
+async fn pause_audio_recording() -> Result<(), String> {
+    audio::service::pause().map_err(|e| e.to_string())
+}
+
+/// Resume a paused recording
+#[tauri::command]
+async fn resume_audio_recording() -> Result<(), String> {
+    audio::service::resume().map_err(|e| e.to_string())
+}
+
+/// Stop the active recording without sending it anywhere
+#[tauri::command]
+async fn discard_audio_recording() -> Result<(), String> {
+    audio::service::discard().map_err(|e| e.to_string())
 }
 
 /// Send audio to AI for transcription/processing
@@ -373,6 +428,102 @@ async fn send_audio_to_ai(
     Ok(response.text)
 }
 
+// ============================================================================
+// Tauri Commands - Post-Action Reports
+// ============================================================================
+
+/// Get reports of recent post-action runs, newest first
+#[tauri::command]
+async fn get_post_action_history() -> Vec<postaction::report::ExecutionReport> {
+    postaction::report::history()
+}
+
+/// Clear the post-action run history
+#[tauri::command]
+async fn clear_post_action_history() {
+    postaction::report::clear_history();
+}
+
+// ============================================================================
+// Tauri Commands - Dry Runs
+// ============================================================================
+
+/// Dry-run a hotkey and return the timeline of what would have happened
+#[tauri::command]
+async fn dry_run_hotkey(id: String) -> Result<backend::dry_run::DryRunReport, String> {
+    hotkey::manager::dry_run_configured(&id).map_err(|e| e.to_string())
+}
+
+/// Get recent dry runs, newest first
+#[tauri::command]
+async fn get_dry_run_history() -> Vec<backend::dry_run::DryRunReport> {
+    backend::dry_run::history()
+}
+
+// ============================================================================
+// Tauri Commands - Prompts
+// ============================================================================
+
+/// Get the label of a pending `{prompt:Label}` placeholder
+#[tauri::command]
+async fn get_prompt(id: String) -> Option<String> {
+    prompt::label(&id)
+}
+
+/// Answer a pending prompt; no value cancels it
+#[tauri::command]
+async fn answer_prompt(id: String, value: Option<String>) {
+    prompt::answer(&id, value);
+}
+
+// ============================================================================
+// Tauri Commands - Recording Archive
+// ============================================================================
+
+/// List archived recordings, newest first
+#[tauri::command]
+async fn list_archived_recordings() -> Result<Vec<audio::archive::ArchivedRecording>, String> {
+    audio::archive::list().map_err(|e| e.to_string())
+}
+
+/// Re-run an archived recording through a role and return the AI response
+#[tauri::command]
+async fn reprocess_archived_recording(id: String, role_id: String) -> Result<String, String> {
+    let app_config = config::manager::load_config().map_err(|e| e.to_string())?;
+    let (entry, audio_data) = audio::archive::load(&id).map_err(|e| e.to_string())?;
+
+    let (samples, sample_rate, channels) =
+        audio::decoder::decode_bytes(audio_data.clone(), None).map_err(|e| e.to_string())?;
+
+    let result = if audio::chunker::needs_chunking(
+        samples.len(),
+        sample_rate,
+        channels,
+        &app_config.ai.chunking,
+    ) {
+        ai::runner::run_long_audio(
+            &app_config.ai,
+            &role_id,
+            &samples,
+            sample_rate,
+            channels,
+            |_, _| {},
+        )
+        .await
+    } else {
+        ai::runner::run_audio(&app_config.ai, &role_id, &audio_data, &entry.mime_type).await
+    };
+
+    audio::archive::record_result(&id, &role_id, &result).map_err(|e| e.to_string())?;
+    result.map_err(|e| e.to_string())
+}
+
+/// Delete an archived recording
+#[tauri::command]
+async fn delete_archived_recording(id: String) -> Result<(), String> {
+    audio::archive::delete(&id).map_err(|e| e.to_string())
+}
+
 // ============================================================================
 // Application Entry Point
 // ============================================================================
@@ -391,7 +542,6 @@ pub fn run() {
         .setup(|app| {
             use tauri::menu::{Menu, MenuItem};
             use tauri::tray::TrayIconBuilder;
-            use tauri::Manager;
 
             // Hide dock icon on macOS - this is a menu bar app
             #[cfg(target_os = "macos")]
@@ -430,17 +580,8 @@ pub fn run() {
                 .tooltip("Global Hotkey")
                 .menu(&menu)
                 .show_menu_on_left_click(true)
-                .on_menu_event(|app, event| match event.id.as_ref() {
-                    "settings" => {
-                        if let Some(window) = app.get_webview_window("main") {
-                            let _ = window.show();
-                            let _ = window.set_focus();
-                        }
-                    }
-                    "quit" => {
-                        app.exit(0);
-                    }
-                    _ => {}
+                .on_menu_event(|app, event| {
+                    tray::handle_menu_event(app, event.id.as_ref());
                 })
                 .build(app)?;
 
@@ -450,6 +591,13 @@ pub fn run() {
                 *tray_ref = Some(tray);
             }
 
+            // Prune the recording archive according to its retention policy
+            if let Some(cfg) = loaded_config.as_ref().filter(|c| c.ai.archive.enabled) {
+                if let Err(e) = audio::archive::apply_retention(&cfg.ai.archive) {
+                    eprintln!("Failed to apply recording archive retention: {}", e);
+                }
+            }
+
             // Register saved hotkeys
             if let Some(cfg) = loaded_config {
                 for hk in cfg.hotkeys.iter().filter(|h| h.enabled) {
@@ -488,8 +636,12 @@ pub fn run() {
             get_registered_hotkeys,
             // Process commands
             launch_program,
+            get_processes,
+            kill_process,
+            get_program_log,
             validate_program_path,
             get_executable_extensions,
+            list_applications,
             // Tray commands
             update_tray_menu,
             update_tray_icon,
@@ -506,7 +658,24 @@ pub fn run() {
             start_audio_recording,
             stop_audio_recording,
             is_audio_recording,
+            get_recording_status,
+            pause_audio_recording,
+            resume_audio_recording,
+            discard_audio_recording,
             send_audio_to_ai,
+            // Post-action report commands
+            get_post_action_history,
+            clear_post_action_history,
+            // Dry run commands
+            dry_run_hotkey,
+            get_dry_run_history,
+            // Prompt commands
+            get_prompt,
+            answer_prompt,
+            // Recording archive commands
+            list_archived_recordings,
+            reprocess_archived_recording,
+            delete_archived_recording,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
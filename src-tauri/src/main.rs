@@ -2,5 +2,9 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 fn main() {
+    if let Some(code) = global_hotkey_lib::run_cli() {
+        std::process::exit(code);
+    }
+
     global_hotkey_lib::run()
 }
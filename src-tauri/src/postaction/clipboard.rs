@@ -0,0 +1,44 @@
+//! Clipboard snapshots for restoring the user's clipboard after a paste
+
+use arboard::{Clipboard, ImageData};
+
+use crate::error::AppError;
+
+/// Contents of the clipboard at a point in time
+/// Formats that are not available (or fail to read) are left empty.
+pub struct ClipboardSnapshot {
+    text: Option<String>,
+    html: Option<String>,
+    image: Option<ImageData<'static>>,
+}
+
+impl ClipboardSnapshot {
+    /// Capture the current clipboard contents
+    pub fn capture() -> Result<Self, AppError> {
+        let mut clipboard = Clipboard::new()
+            .map_err(|e| AppError::PostAction(format!("Clipboard error: {}", e)))?;
+
+        Ok(Self {
+            text: clipboard.get_text().ok(),
+            html: clipboard.get().html().ok(),
+            image: clipboard.get_image().ok(),
+        })
+    }
+
+    /// Write the snapshot back to the clipboard
+    /// The clipboard holds a single entry, so HTML (with its text alternative)
+    /// takes precedence over plain text, and text over images.
+    pub fn restore(self) -> Result<(), AppError> {
+        let mut clipboard = Clipboard::new()
+            .map_err(|e| AppError::PostAction(format!("Clipboard error: {}", e)))?;
+
+        let result = match (self.html, self.text, self.image) {
+            (Some(html), text, _) => clipboard.set_html(html, text),
+            (None, Some(text), _) => clipboard.set_text(text),
+            (None, None, Some(image)) => clipboard.set_image(image),
+            (None, None, None) => clipboard.clear(),
+        };
+
+        result.map_err(|e| AppError::PostAction(format!("Failed to restore clipboard: {}", e)))
+    }
+}
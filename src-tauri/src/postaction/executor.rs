@@ -1,109 +1,242 @@
 //! Post-action execution logic
 
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
+use chrono::Utc;
+use regex::Regex;
+
+use crate::backend::dry_run::TimelineKind;
+use crate::backend::Backends;
 use crate::config::schema::{
-    PostAction, PostActionTrigger, PostActionType, PostActionsConfig, ProgramConfig,
+    AiStepInput, ClipboardRestore, OnErrorPolicy, PostAction, PostActionTrigger, PostActionType,
+    PostActionsConfig, ProgramConfig,
 };
 use crate::error::AppError;
-use crate::process;
+use crate::process::script;
+use crate::template::{self, TemplateContext};
+
+use super::clipboard::ClipboardSnapshot;
+use super::report::{self, ExecutionReport, StepReport, StepStatus};
+
+/// Pause between retries of a failed step
+const RETRY_DELAY_MS: u64 = 250;
 
-use super::input::InputSimulator;
+/// Pause before each step so window focus is stable
+const FOCUS_SETTLE_MS: u64 = 50;
 
 /// Execute a program with post-actions
 pub fn execute_with_post_actions(
     program_config: &ProgramConfig,
     post_actions: &PostActionsConfig,
     hotkey_name: &str,
+    backends: &mut Backends,
 ) -> Result<(), AppError> {
     // If no post-actions enabled, just launch normally
     if !post_actions.enabled || post_actions.actions.is_empty() {
-        return process::spawner::launch(program_config);
+        return backends.process.launch(program_config);
     }
 
+    let mut context = TemplateContext {
+        hotkey_name: Some(hotkey_name.to_string()),
+        ..Default::default()
+    };
+
+    // Snapshot before launching, since the program may overwrite the clipboard
+    let mut clipboard = capture_clipboard_if_needed(post_actions, backends);
+
     match &post_actions.trigger {
-        PostActionTrigger::OnExit => {
+        PostActionTrigger::AfterDelay { delay_ms } => {
+            // Launch process (don't wait)
+            backends.process.launch(program_config)?;
+
+            // Wait for delay then execute post-actions
+            backends.wait(*delay_ms);
+            execute_actions(
+                &post_actions.actions,
+                &mut context,
+                &mut clipboard,
+                backends,
+            )?;
+        }
+        PostActionTrigger::OnStdoutMatch { pattern } => {
+            // Fails before the program runs, so its script file would be left behind
+            let regex = Regex::new(pattern).map_err(|e| {
+                script::remove_temp_file(program_config);
+                AppError::PostAction(format!("Invalid stdout pattern '{}': {}", pattern, e))
+            })?;
+
+            match backends.process.wait_for_match(program_config, regex)? {
+                Some(line) => {
+                    context.output = Some(line);
+                    execute_actions(
+                        &post_actions.actions,
+                        &mut context,
+                        &mut clipboard,
+                        backends,
+                    )?;
+                }
+                None => eprintln!(
+                    "Hotkey '{}': process exited without matching '{}', skipping post-actions",
+                    hotkey_name, pattern
+                ),
+            }
+        }
+        trigger => {
             // Launch and wait for process to exit
-            let exit_code = launch_and_wait(program_config)?;
+            let capture_output = uses_output(&post_actions.actions);
+            let output = backends
+                .process
+                .launch_and_wait(program_config, capture_output)?;
+            let exit_code = output.exit_code;
 
-            if exit_code == 0 {
-                execute_actions(&post_actions.actions, hotkey_name)?;
+            if trigger.matches_exit_code(exit_code) {
+                context.exit_code = Some(exit_code);
+                context.output = Some(output.stdout);
+                execute_actions(
+                    &post_actions.actions,
+                    &mut context,
+                    &mut clipboard,
+                    backends,
+                )?;
             } else {
+                let reason = if output.timed_out {
+                    " after timing out"
+                } else {
+                    ""
+                };
                 eprintln!(
-                    "Hotkey '{}': process exited with code {}, skipping post-actions",
-                    hotkey_name, exit_code
+                    "Hotkey '{}': process exited with code {}{}, skipping post-actions",
+                    hotkey_name, exit_code, reason
                 );
+                if !output.stderr.trim().is_empty() {
+                    eprintln!("Hotkey '{}' stderr: {}", hotkey_name, output.stderr.trim());
+                }
             }
         }
-        PostActionTrigger::AfterDelay { delay_ms } => {
-            // Launch process (don't wait)
-            process::spawner::launch(program_config)?;
-
-            // Wait for delay then execute post-actions
-            thread::sleep(Duration::from_millis(*delay_ms));
-            execute_actions(&post_actions.actions, hotkey_name)?;
-        }
     }
 
     Ok(())
 }
 
-/// Launch a program and wait for it to exit, returning the exit code
-fn launch_and_wait(config: &ProgramConfig) -> Result<i32, AppError> {
-    use std::process::Command;
-
-    let resolved_path = process::spawner::resolve_program(&config.path)
-        .ok_or_else(|| AppError::Process(format!("Program not found: {}", config.path)))?;
-
-    let mut command = Command::new(&resolved_path);
-
-    // Add arguments
-    for arg in &config.arguments {
-        if !arg.is_empty() {
-            command.arg(arg);
-        }
-    }
-
-    // Set working directory
-    if let Some(ref working_dir) = config.working_directory {
-        if !working_dir.is_empty() {
-            let dir = std::path::Path::new(working_dir);
-            if dir.exists() && dir.is_dir() {
-                command.current_dir(dir);
+/// Check if any enabled step reads the previous step's output, through the
+/// `{output}` placeholder or as AI input
+fn uses_output(actions: &[PostAction]) -> bool {
+    let has_placeholder = |text: &str| text.contains("{output}");
+    actions
+        .iter()
+        .filter(|action| action.enabled)
+        .any(|action| match &action.action_type {
+            PostActionType::TypeText { text } => has_placeholder(text),
+            PostActionType::RunProgram { program, .. } => {
+                program.arguments.iter().any(|a| has_placeholder(a))
             }
-        }
-    }
+            PostActionType::CallAi { input, .. } => *input == AiStepInput::PreviousOutput,
+            _ => false,
+        })
+}
 
-    // Apply hidden mode if configured
-    if config.hidden {
-        process::platform::configure_hidden(&mut command);
-    }
+/// Check if any paste step restores the clipboard afterwards
+pub fn needs_clipboard_snapshot(post_actions: &PostActionsConfig) -> bool {
+    post_actions.enabled
+        && post_actions.actions.iter().any(|action| {
+            action.enabled
+                && matches!(
+                    action.action_type,
+                    PostActionType::PasteClipboard {
+                        restore_clipboard: Some(_)
+                    }
+                )
+        })
+}
 
-    // NOTE: Don't detach - we need to wait for this process
-    let output = command.output().map_err(|e| {
-        AppError::Process(format!("Failed to launch program '{}': {}", config.path, e))
-    })?;
+/// Capture the clipboard if a paste step will restore it
+fn capture_clipboard_if_needed(
+    post_actions: &PostActionsConfig,
+    backends: &mut Backends,
+) -> Option<ClipboardSnapshot> {
+    if !needs_clipboard_snapshot(post_actions) {
+        return None;
+    }
 
-    Ok(output.status.code().unwrap_or(-1))
+    backends
+        .clipboard
+        .capture()
+        .map_err(|e| eprintln!("Failed to snapshot clipboard: {}", e))
+        .ok()
 }
 
 /// Execute post-actions directly (for AI actions where the "process" is the AI call itself)
-pub fn execute_post_actions(post_actions: &PostActionsConfig) -> Result<(), AppError> {
+/// `ai_response` is made available to the `{ai_response}` placeholder.
+/// `clipboard` is the clipboard from before the AI call; it is restored by a paste step
+/// that asks for it, or after all steps if `restore` is set.
+pub fn execute_post_actions(
+    post_actions: &PostActionsConfig,
+    hotkey_name: &str,
+    ai_response: Option<String>,
+    mut clipboard: Option<ClipboardSnapshot>,
+    restore: Option<&ClipboardRestore>,
+    backends: &mut Backends,
+) -> Result<(), AppError> {
     if !post_actions.enabled || post_actions.actions.is_empty() {
         return Ok(());
     }
 
+    let result = execute_post_actions_inner(
+        post_actions,
+        hotkey_name,
+        ai_response,
+        &mut clipboard,
+        backends,
+    );
+
+    if let (Some(restore), Some(snapshot)) = (restore, clipboard) {
+        restore_clipboard(snapshot, restore, backends)?;
+    }
+
+    result
+}
+
+fn execute_post_actions_inner(
+    post_actions: &PostActionsConfig,
+    hotkey_name: &str,
+    ai_response: Option<String>,
+    clipboard: &mut Option<ClipboardSnapshot>,
+    backends: &mut Backends,
+) -> Result<(), AppError> {
+    let mut context = TemplateContext {
+        hotkey_name: Some(hotkey_name.to_string()),
+        output: ai_response.clone(),
+        ai_response,
+        ..Default::default()
+    };
+
     // For AI actions, we handle the trigger differently:
-    // - OnExit: Execute immediately (AI action already completed)
+    // - Exit triggers: the AI action completed successfully, i.e. "exit code 0"
     // - AfterDelay: Wait then execute
+    // - OnStdoutMatch: Match against the lines of the AI response
     match &post_actions.trigger {
-        PostActionTrigger::OnExit => {
-            execute_actions(&post_actions.actions, "AI action")?;
-        }
         PostActionTrigger::AfterDelay { delay_ms } => {
-            std::thread::sleep(std::time::Duration::from_millis(*delay_ms));
-            execute_actions(&post_actions.actions, "AI action")?;
+            backends.wait(*delay_ms);
+            execute_actions(&post_actions.actions, &mut context, clipboard, backends)?;
+        }
+        PostActionTrigger::OnStdoutMatch { pattern } => {
+            let regex = Regex::new(pattern).map_err(|e| {
+                AppError::PostAction(format!("Invalid stdout pattern '{}': {}", pattern, e))
+            })?;
+
+            let matched = context
+                .ai_response
+                .as_deref()
+                .is_some_and(|response| response.lines().any(|line| regex.is_match(line)));
+            if matched {
+                execute_actions(&post_actions.actions, &mut context, clipboard, backends)?;
+            }
+        }
+        trigger => {
+            if trigger.matches_exit_code(0) {
+                execute_actions(&post_actions.actions, &mut context, clipboard, backends)?;
+            }
         }
     }
 
@@ -111,30 +244,204 @@ pub fn execute_post_actions(post_actions: &PostActionsConfig) -> Result<(), AppE
 }
 
 /// Execute a sequence of post-actions
-fn execute_actions(actions: &[PostAction], hotkey_name: &str) -> Result<(), AppError> {
-    let mut simulator = InputSimulator::new()?;
+/// Steps record their results (e.g. exit codes) in the context for later steps
+/// `clipboard` holds the snapshot taken before the run, consumed by a restoring paste step
+fn execute_actions(
+    actions: &[PostAction],
+    context: &mut TemplateContext,
+    clipboard: &mut Option<ClipboardSnapshot>,
+    backends: &mut Backends,
+) -> Result<(), AppError> {
+    let hotkey_name = context.hotkey_name.clone().unwrap_or_default();
+    let started_at = Utc::now();
+    let run_start = Instant::now();
+
+    let mut steps = Vec::with_capacity(actions.len());
+    let mut abort_error: Option<AppError> = None;
+    let mut success = true;
+
+    for (index, action) in actions.iter().enumerate() {
+        let mut step = StepReport {
+            index,
+            action_id: action.id.clone(),
+            action_type: report::action_type_name(&action.action_type),
+            status: StepStatus::Skipped,
+            attempts: 0,
+            duration_ms: 0,
+            error: None,
+        };
 
-    for action in actions {
-        if !action.enabled {
+        if !action.enabled || abort_error.is_some() {
+            steps.push(step);
             continue;
         }
 
-        // Small delay before simulating input to ensure window focus is stable
-        thread::sleep(Duration::from_millis(50));
+        backends.note(
+            TimelineKind::Info,
+            format!("Step {}: {}", index + 1, step.action_type),
+        );
 
-        match &action.action_type {
-            PostActionType::PasteClipboard => {
-                simulator.paste()?;
+        let max_attempts = match action.on_error {
+            OnErrorPolicy::Retry { attempts } => attempts.saturating_add(1),
+            OnErrorPolicy::Abort | OnErrorPolicy::Continue => 1,
+        };
+        let step_start = Instant::now();
+
+        let result = loop {
+            step.attempts += 1;
+
+            // Small delay before simulating input to ensure window focus is stable
+            // (not worth a timeline entry per step in dry runs)
+            if !backends.is_dry_run() {
+                thread::sleep(Duration::from_millis(FOCUS_SETTLE_MS));
             }
-            PostActionType::SimulateKeystroke { keystroke } => {
-                simulator.simulate_keystroke(keystroke)?;
+
+            // Only keep a waited program's output if a later step reads it
+            let capture_output = uses_output(&actions[index + 1..]);
+            match execute_action(
+                &action.action_type,
+                context,
+                clipboard,
+                capture_output,
+                backends,
+            ) {
+                Ok(()) => break Ok(()),
+                Err(e) if step.attempts < max_attempts => {
+                    eprintln!(
+                        "Hotkey '{}': step {} failed (attempt {} of {}), retrying: {}",
+                        hotkey_name,
+                        index + 1,
+                        step.attempts,
+                        max_attempts,
+                        e
+                    );
+                    backends.wait(RETRY_DELAY_MS);
+                }
+                Err(e) => break Err(e),
             }
-            PostActionType::Delay { delay_ms } => {
-                thread::sleep(Duration::from_millis(*delay_ms));
+        };
+
+        step.duration_ms = step_start.elapsed().as_millis() as u64;
+        match result {
+            Ok(()) => step.status = StepStatus::Succeeded,
+            Err(e) => {
+                eprintln!("Hotkey '{}': step {} failed: {}", hotkey_name, index + 1, e);
+                success = false;
+                step.error = Some(e.to_string());
+                if action.on_error == OnErrorPolicy::Continue {
+                    step.status = StepStatus::Failed;
+                } else {
+                    step.status = StepStatus::Aborted;
+                    abort_error = Some(e);
+                }
             }
         }
+        steps.push(step);
+    }
+
+    report::record(ExecutionReport {
+        id: uuid::Uuid::new_v4().to_string(),
+        hotkey_name: hotkey_name.clone(),
+        started_at: started_at.to_rfc3339(),
+        duration_ms: run_start.elapsed().as_millis() as u64,
+        success,
+        dry_run: backends.is_dry_run(),
+        steps,
+    });
+
+    if let Some(e) = abort_error {
+        return Err(e);
     }
 
     eprintln!("Hotkey '{}': post-actions completed", hotkey_name);
     Ok(())
 }
+
+/// Execute a single post-action step
+/// `capture_output` tells a waited program step whether later steps read its output
+fn execute_action(
+    action_type: &PostActionType,
+    context: &mut TemplateContext,
+    clipboard: &mut Option<ClipboardSnapshot>,
+    capture_output: bool,
+    backends: &mut Backends,
+) -> Result<(), AppError> {
+    match action_type {
+        PostActionType::PasteClipboard { restore_clipboard } => {
+            backends.input.paste()?;
+
+            if let (Some(restore), Some(snapshot)) = (restore_clipboard, clipboard.take()) {
+                self::restore_clipboard(snapshot, restore, backends)?;
+            }
+        }
+        PostActionType::SimulateKeystroke { keystroke } => {
+            backends.input.simulate_keystroke(keystroke)?;
+        }
+        PostActionType::Delay { delay_ms } => {
+            backends.wait(*delay_ms);
+        }
+        PostActionType::TypeText { text } => {
+            let text = template::expand(text, context, backends)?;
+            backends.input.type_text(&text)?;
+        }
+        PostActionType::RunProgram {
+            program,
+            wait_for_exit,
+        } => {
+            let program = &template::expand_program(program, context, backends)?;
+            if *wait_for_exit {
+                let output = backends.process.launch_and_wait(program, capture_output)?;
+                eprintln!("Post-action program exited with code {}", output.exit_code);
+                context.exit_code = Some(output.exit_code);
+                context.output = Some(output.stdout);
+            } else if backends.check_instance_policy(
+                program,
+                context.hotkey_name.as_deref().unwrap_or_default(),
+            )? {
+                backends.process.launch(program)?;
+            }
+        }
+        PostActionType::CallAi { role_id, input } => {
+            let response = call_ai(role_id, input, context, backends)?;
+            context.output = Some(response.clone());
+            context.ai_response = Some(response);
+        }
+        PostActionType::InputScript { steps } => {
+            backends.input.run_script(steps)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Run an AI role on the clipboard or the previous step's output
+/// The response is written to the clipboard and returned
+fn call_ai(
+    role_id: &str,
+    input: &AiStepInput,
+    context: &TemplateContext,
+    backends: &mut Backends,
+) -> Result<String, AppError> {
+    let text = match input {
+        AiStepInput::Clipboard => backends.clipboard.get_text()?,
+        AiStepInput::PreviousOutput => context
+            .output
+            .clone()
+            .ok_or_else(|| AppError::Ai("No output from a previous step".to_string()))?,
+    };
+
+    let response = backends.ai.run_text(role_id, &text)?;
+    backends.clipboard.set_text(&response)?;
+
+    Ok(response)
+}
+
+/// Restore a clipboard snapshot once the settle delay has passed
+fn restore_clipboard(
+    snapshot: ClipboardSnapshot,
+    restore: &ClipboardRestore,
+    backends: &mut Backends,
+) -> Result<(), AppError> {
+    backends.wait(restore.settle_delay_ms);
+    backends.clipboard.restore(snapshot)
+}
@@ -1,8 +1,11 @@
 //! Keystroke simulation using enigo
 
-use enigo::{Direction, Enigo, Key, Keyboard, Settings};
+use std::thread;
+use std::time::Duration;
 
-use crate::config::schema::Keystroke;
+use enigo::{Axis, Button, Coordinate, Direction, Enigo, Key, Keyboard, Mouse, Settings};
+
+use crate::config::schema::{InputStep, Keystroke, MouseButton};
 use crate::error::AppError;
 
 pub struct InputSimulator {
@@ -65,6 +68,107 @@ impl InputSimulator {
         Ok(())
     }
 
+    /// Type a string using the platform's text entry
+    pub fn type_text(&mut self, text: &str) -> Result<(), AppError> {
+        self.enigo
+            .text(text)
+            .map_err(|e| AppError::PostAction(format!("Failed to type text: {}", e)))
+    }
+
+    /// Run an input script
+    /// Keys that are still pressed when the script ends or fails are released.
+    pub fn run_script(&mut self, steps: &[InputStep]) -> Result<(), AppError> {
+        let mut pressed = Vec::new();
+        let result = self.run_steps(steps, &mut pressed);
+
+        for key in pressed.into_iter().rev() {
+            let _ = self.enigo.key(key, Direction::Release);
+        }
+
+        result
+    }
+
+    fn run_steps(&mut self, steps: &[InputStep], pressed: &mut Vec<Key>) -> Result<(), AppError> {
+        for step in steps {
+            match step {
+                InputStep::Press { key } => {
+                    let key = self.map_script_key(key)?;
+                    self.key(key, Direction::Press)?;
+                    pressed.push(key);
+                }
+                InputStep::Release { key } => {
+                    let key = self.map_script_key(key)?;
+                    self.key(key, Direction::Release)?;
+                    pressed.retain(|k| *k != key);
+                }
+                InputStep::Tap { key } => {
+                    let key = self.map_script_key(key)?;
+                    self.key(key, Direction::Click)?;
+                }
+                InputStep::Hold { key, duration_ms } => {
+                    let key = self.map_script_key(key)?;
+                    self.key(key, Direction::Press)?;
+                    pressed.push(key);
+                    thread::sleep(Duration::from_millis(*duration_ms));
+                    self.key(key, Direction::Release)?;
+                    pressed.retain(|k| *k != key);
+                }
+                InputStep::Repeat { times, steps } => {
+                    for _ in 0..*times {
+                        self.run_steps(steps, pressed)?;
+                    }
+                }
+                InputStep::TypeUnicode { text } => self.type_text(text)?,
+                InputStep::Wait { delay_ms } => {
+                    thread::sleep(Duration::from_millis(*delay_ms));
+                }
+                InputStep::MouseMove { x, y, relative } => {
+                    let coordinate = if *relative {
+                        Coordinate::Rel
+                    } else {
+                        Coordinate::Abs
+                    };
+                    self.enigo.move_mouse(*x, *y, coordinate).map_err(|e| {
+                        AppError::PostAction(format!("Failed to move mouse: {}", e))
+                    })?;
+                }
+                InputStep::Click { button } => {
+                    let button = match button {
+                        MouseButton::Left => Button::Left,
+                        MouseButton::Middle => Button::Middle,
+                        MouseButton::Right => Button::Right,
+                    };
+                    self.enigo
+                        .button(button, Direction::Click)
+                        .map_err(|e| AppError::PostAction(format!("Failed to click: {}", e)))?;
+                }
+                InputStep::Scroll { amount, horizontal } => {
+                    let axis = if *horizontal {
+                        Axis::Horizontal
+                    } else {
+                        Axis::Vertical
+                    };
+                    self.enigo
+                        .scroll(*amount, axis)
+                        .map_err(|e| AppError::PostAction(format!("Failed to scroll: {}", e)))?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn key(&mut self, key: Key, direction: Direction) -> Result<(), AppError> {
+        self.enigo
+            .key(key, direction)
+            .map_err(|e| AppError::PostAction(format!("Failed to simulate key: {}", e)))
+    }
+
+    /// Map a script key name, which may also be a modifier
+    fn map_script_key(&self, key: &str) -> Result<Key, AppError> {
+        self.map_modifier(key).or_else(|_| self.map_key(key))
+    }
+
     fn map_modifier(&self, modifier: &str) -> Result<Key, AppError> {
         match modifier.to_lowercase().as_str() {
             "ctrl" | "control" => Ok(Key::Control),
@@ -110,7 +214,110 @@ impl InputSimulator {
             "F10" => Ok(Key::F10),
             "F11" => Ok(Key::F11),
             "F12" => Ok(Key::F12),
-            _ => Err(AppError::PostAction(format!("Unknown key: {}", key))),
+            "F13" => Ok(Key::F13),
+            "F14" => Ok(Key::F14),
+            "F15" => Ok(Key::F15),
+            "F16" => Ok(Key::F16),
+            "F17" => Ok(Key::F17),
+            "F18" => Ok(Key::F18),
+            "F19" => Ok(Key::F19),
+            "F20" => Ok(Key::F20),
+            #[cfg(not(target_os = "macos"))]
+            "F21" => Ok(Key::F21),
+            #[cfg(not(target_os = "macos"))]
+            "F22" => Ok(Key::F22),
+            #[cfg(not(target_os = "macos"))]
+            "F23" => Ok(Key::F23),
+            #[cfg(not(target_os = "macos"))]
+            "F24" => Ok(Key::F24),
+            // Media keys
+            "MEDIAPLAYPAUSE" | "PLAYPAUSE" => Ok(Key::MediaPlayPause),
+            "MEDIANEXT" | "MEDIANEXTTRACK" | "MEDIATRACKNEXT" => Ok(Key::MediaNextTrack),
+            "MEDIAPREV" | "MEDIAPREVTRACK" | "MEDIATRACKPREVIOUS" => Ok(Key::MediaPrevTrack),
+            #[cfg(not(target_os = "macos"))]
+            "MEDIASTOP" => Ok(Key::MediaStop),
+            "VOLUMEUP" | "AUDIOVOLUMEUP" => Ok(Key::VolumeUp),
+            "VOLUMEDOWN" | "AUDIOVOLUMEDOWN" => Ok(Key::VolumeDown),
+            "VOLUMEMUTE" | "AUDIOVOLUMEMUTE" | "MUTE" => Ok(Key::VolumeMute),
+            // Punctuation by name
+            "COMMA" => Ok(Key::Unicode(',')),
+            "PERIOD" | "DOT" => Ok(Key::Unicode('.')),
+            "SLASH" => Ok(Key::Unicode('/')),
+            "BACKSLASH" => Ok(Key::Unicode('\\')),
+            "SEMICOLON" => Ok(Key::Unicode(';')),
+            "QUOTE" | "APOSTROPHE" => Ok(Key::Unicode('\'')),
+            "BACKQUOTE" | "BACKTICK" | "GRAVE" => Ok(Key::Unicode('`')),
+            "MINUS" | "DASH" => Ok(Key::Unicode('-')),
+            "EQUAL" | "EQUALS" => Ok(Key::Unicode('=')),
+            "BRACKETLEFT" | "LEFTBRACKET" => Ok(Key::Unicode('[')),
+            "BRACKETRIGHT" | "RIGHTBRACKET" => Ok(Key::Unicode(']')),
+            other => map_numpad_key(other)
+                .ok_or_else(|| AppError::PostAction(format!("Unknown key: {}", key))),
         }
     }
 }
+
+/// Map numpad key names (`Numpad0`-`Numpad9`, `NumpadAdd`, `NumpadEnter`, ...)
+/// enigo only has named numpad keys on Windows, so other platforms use raw
+/// X11 keysyms or macOS virtual key codes.
+fn map_numpad_key(name: &str) -> Option<Key> {
+    let name = name.strip_prefix("NUMPAD")?;
+
+    #[cfg(target_os = "windows")]
+    let key = match name {
+        "0" => Key::Numpad0,
+        "1" => Key::Numpad1,
+        "2" => Key::Numpad2,
+        "3" => Key::Numpad3,
+        "4" => Key::Numpad4,
+        "5" => Key::Numpad5,
+        "6" => Key::Numpad6,
+        "7" => Key::Numpad7,
+        "8" => Key::Numpad8,
+        "9" => Key::Numpad9,
+        "ADD" => Key::Add,
+        "SUBTRACT" => Key::Subtract,
+        "MULTIPLY" => Key::Multiply,
+        "DIVIDE" => Key::Divide,
+        "DECIMAL" => Key::Decimal,
+        "ENTER" => Key::Return,
+        _ => return None,
+    };
+
+    #[cfg(target_os = "macos")]
+    let key = Key::Other(match name {
+        "0" => 0x52,
+        "1" => 0x53,
+        "2" => 0x54,
+        "3" => 0x55,
+        "4" => 0x56,
+        "5" => 0x57,
+        "6" => 0x58,
+        "7" => 0x59,
+        "8" => 0x5B,
+        "9" => 0x5C,
+        "ADD" => 0x45,
+        "SUBTRACT" => 0x4E,
+        "MULTIPLY" => 0x43,
+        "DIVIDE" => 0x4B,
+        "DECIMAL" => 0x41,
+        "ENTER" => 0x4C,
+        _ => return None,
+    });
+
+    #[cfg(all(unix, not(target_os = "macos")))]
+    let key = Key::Other(match name {
+        "ADD" => 0xffab,
+        "SUBTRACT" => 0xffad,
+        "MULTIPLY" => 0xffaa,
+        "DIVIDE" => 0xffaf,
+        "DECIMAL" => 0xffae,
+        "ENTER" => 0xff8d,
+        digit => match digit.parse::<u32>() {
+            Ok(n) if n <= 9 => 0xffb0 + n,
+            _ => return None,
+        },
+    });
+
+    Some(key)
+}
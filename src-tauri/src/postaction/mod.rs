@@ -1,7 +1,10 @@
 //! Post-action execution module
 
+pub mod clipboard;
 mod executor;
-mod input;
+pub mod input;
+pub mod report;
 
 pub use executor::execute_post_actions;
 pub use executor::execute_with_post_actions;
+pub use executor::needs_clipboard_snapshot;
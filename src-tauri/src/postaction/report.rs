@@ -0,0 +1,97 @@
+//! Structured execution reports for post-action runs
+//!
+//! Each run is emitted to the frontend as a `post-action-report` event and kept
+//! in an in-memory history of the most recent runs.
+
+use std::collections::VecDeque;
+use std::sync::RwLock;
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+
+use crate::config::schema::PostActionType;
+use crate::tray::emit_event;
+
+/// Number of runs kept in the history
+const MAX_HISTORY: usize = 50;
+
+/// Outcome of a single step
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StepStatus {
+    Succeeded,
+    /// Failed, but the sequence continued (on-error policy "continue")
+    Failed,
+    /// Failed and aborted the sequence
+    Aborted,
+    /// Disabled, or not reached because an earlier step aborted
+    Skipped,
+}
+
+/// Report for a single step
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StepReport {
+    pub index: usize,
+    pub action_id: String,
+    /// Action type, e.g. "pasteClipboard"
+    pub action_type: &'static str,
+    pub status: StepStatus,
+    /// Number of attempts made (0 for skipped steps)
+    pub attempts: u32,
+    pub duration_ms: u64,
+    /// Error of the last attempt
+    pub error: Option<String>,
+}
+
+/// Report for a complete post-action run
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutionReport {
+    pub id: String,
+    pub hotkey_name: String,
+    pub started_at: String,
+    pub duration_ms: u64,
+    /// True if every executed step succeeded
+    pub success: bool,
+    /// True if the run was a dry run and had no real side effects
+    pub dry_run: bool,
+    pub steps: Vec<StepReport>,
+}
+
+static HISTORY: Lazy<RwLock<VecDeque<ExecutionReport>>> =
+    Lazy::new(|| RwLock::new(VecDeque::with_capacity(MAX_HISTORY)));
+
+/// Get the serialized type name of an action
+pub fn action_type_name(action_type: &PostActionType) -> &'static str {
+    match action_type {
+        PostActionType::PasteClipboard { .. } => "pasteClipboard",
+        PostActionType::SimulateKeystroke { .. } => "simulateKeystroke",
+        PostActionType::Delay { .. } => "delay",
+        PostActionType::TypeText { .. } => "typeText",
+        PostActionType::RunProgram { .. } => "runProgram",
+        PostActionType::CallAi { .. } => "callAi",
+        PostActionType::InputScript { .. } => "inputScript",
+    }
+}
+
+/// Emit a report to the frontend and add it to the history
+pub fn record(report: ExecutionReport) {
+    emit_event("post-action-report", report.clone());
+
+    let mut history = HISTORY.write().unwrap();
+    if history.len() == MAX_HISTORY {
+        history.pop_back();
+    }
+    history.push_front(report);
+}
+
+/// Get the recorded runs, newest first
+pub fn history() -> Vec<ExecutionReport> {
+    HISTORY.read().unwrap().iter().cloned().collect()
+}
+
+/// Clear the recorded runs
+pub fn clear_history() {
+    HISTORY.write().unwrap().clear();
+}
@@ -0,0 +1,382 @@
+//! Freedesktop `.desktop` entries
+//!
+//! A program path may name a `.desktop` file, or an application id such as
+//! `org.gnome.Nautilus` that is looked up in the XDG application directories.
+//! The entry's `Exec=` line is turned into a regular program launch; the
+//! configured arguments fill the `%f`/`%F`/`%u`/`%U` field codes.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::config::schema::ProgramConfig;
+use crate::error::AppError;
+
+use super::spawner;
+
+/// The `[Desktop Entry]` group of a `.desktop` file
+#[derive(Debug, Clone)]
+pub struct DesktopEntry {
+    pub name: String,
+    pub exec: String,
+    pub try_exec: Option<String>,
+    /// Working directory
+    pub path: Option<String>,
+    pub terminal: bool,
+    pub icon: Option<String>,
+    pub comment: Option<String>,
+    /// Not meant to be shown in application menus
+    pub hidden: bool,
+    /// The file the entry was read from
+    pub file: PathBuf,
+}
+
+/// An installed application, for the app picker
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DesktopApp {
+    /// Desktop file id, usable as a program path
+    pub id: String,
+    pub name: String,
+    pub comment: Option<String>,
+    pub icon: Option<String>,
+    pub exec: String,
+    pub file: String,
+}
+
+/// Resolve a program that points to a desktop entry into the program it runs
+/// Other programs are returned unchanged.
+pub fn expand(config: &ProgramConfig) -> Result<Cow<'_, ProgramConfig>, AppError> {
+    let Some(file) = find(&config.path) else {
+        return Ok(Cow::Borrowed(config));
+    };
+
+    let entry = parse(&file)?;
+
+    if let Some(try_exec) = entry.try_exec.as_deref().filter(|t| !t.is_empty()) {
+        if spawner::resolve_program(try_exec).is_none() {
+            return Err(AppError::Process(format!(
+                "'{}' is not installed ({} not found)",
+                entry.name, try_exec
+            )));
+        }
+    }
+
+    let mut argv = exec_arguments(&entry, &config.arguments)?;
+
+    let working_directory = config
+        .working_directory
+        .clone()
+        .filter(|dir| !dir.is_empty())
+        .or(entry.path.clone());
+
+    Ok(Cow::Owned(ProgramConfig {
+        path: argv.remove(0),
+        arguments: argv,
+        working_directory,
+        run_in_terminal: config.run_in_terminal || entry.terminal,
+        ..config.clone()
+    }))
+}
+
+/// Check whether a program path names a desktop entry
+pub fn is_desktop_entry(path: &str) -> bool {
+    find(path).is_some()
+}
+
+/// List the applications installed in the XDG application directories
+/// Entries in earlier directories (the user's own) hide those with the same id.
+pub fn list_applications() -> Vec<DesktopApp> {
+    // Hidden entries are kept as `None` so they still hide lower-priority ones
+    let mut apps: HashMap<String, Option<DesktopApp>> = HashMap::new();
+
+    for dir in application_dirs() {
+        for file in desktop_files(&dir) {
+            let Some(id) = desktop_file_id(&dir, &file) else {
+                continue;
+            };
+            if apps.contains_key(&id) {
+                continue;
+            }
+            let Ok(entry) = parse(&file) else {
+                continue;
+            };
+
+            let app = (!entry.hidden).then(|| DesktopApp {
+                id: id.clone(),
+                name: entry.name,
+                comment: entry.comment,
+                icon: entry.icon,
+                exec: entry.exec,
+                file: file.to_string_lossy().into_owned(),
+            });
+            apps.insert(id, app);
+        }
+    }
+
+    let mut apps: Vec<DesktopApp> = apps.into_values().flatten().collect();
+    apps.sort_by_key(|a| a.name.to_lowercase());
+    apps
+}
+
+/// Find the desktop file a program path refers to
+/// Bare names only count as application ids if they are not on PATH.
+fn find(path: &str) -> Option<PathBuf> {
+    let candidate = Path::new(path);
+    if path.ends_with(".desktop") && candidate.is_file() {
+        return Some(candidate.to_path_buf());
+    }
+
+    if path.is_empty() || path.contains(['/', '\\']) {
+        return None;
+    }
+    if !path.ends_with(".desktop") && spawner::resolve_program(path).is_some() {
+        return None;
+    }
+
+    let file_name = if path.ends_with(".desktop") {
+        path.to_string()
+    } else {
+        format!("{}.desktop", path)
+    };
+
+    application_dirs().into_iter().find_map(|dir| {
+        // Ids of entries in subdirectories use '-' in place of '/'
+        let direct = dir.join(&file_name);
+        if direct.is_file() {
+            return Some(direct);
+        }
+        desktop_files(&dir).into_iter().find(|file| {
+            desktop_file_id(&dir, file).as_deref() == Some(file_name.trim_end_matches(".desktop"))
+        })
+    })
+}
+
+/// Read the `[Desktop Entry]` group of a desktop file
+pub fn parse(file: &Path) -> Result<DesktopEntry, AppError> {
+    let content = std::fs::read_to_string(file)?;
+
+    let mut values: HashMap<&str, String> = HashMap::new();
+    let mut in_entry = false;
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if line.starts_with('[') {
+            in_entry = line == "[Desktop Entry]";
+            continue;
+        }
+        if !in_entry {
+            continue;
+        }
+        // Localized keys like Name[de] are skipped
+        if let Some((key, value)) = line.split_once('=') {
+            let key = key.trim();
+            if !key.contains('[') {
+                values.insert(key, unescape(value.trim()));
+            }
+        }
+    }
+
+    let invalid = |reason: &str| {
+        AppError::Process(format!(
+            "Invalid desktop entry {}: {}",
+            file.display(),
+            reason
+        ))
+    };
+
+    if values.get("Type").is_some_and(|t| t != "Application") {
+        return Err(invalid("not an application"));
+    }
+    let exec = values
+        .remove("Exec")
+        .ok_or_else(|| invalid("no Exec key"))?;
+    let is_true = |value: Option<&String>| value.is_some_and(|v| v == "true");
+
+    Ok(DesktopEntry {
+        name: values.remove("Name").unwrap_or_default(),
+        try_exec: values.remove("TryExec"),
+        path: values.remove("Path").filter(|p| !p.is_empty()),
+        terminal: is_true(values.get("Terminal")),
+        icon: values.remove("Icon"),
+        comment: values.remove("Comment"),
+        hidden: is_true(values.get("NoDisplay")) || is_true(values.get("Hidden")),
+        exec,
+        file: file.to_path_buf(),
+    })
+}
+
+/// Build the argument vector from `Exec=`, filling in field codes
+fn exec_arguments(entry: &DesktopEntry, files: &[String]) -> Result<Vec<String>, AppError> {
+    let mut argv = Vec::new();
+
+    for token in split_exec(&entry.exec) {
+        match token.as_str() {
+            "%f" | "%u" => argv.extend(files.first().cloned()),
+            "%F" | "%U" => argv.extend(files.iter().cloned()),
+            "%i" => {
+                if let Some(icon) = &entry.icon {
+                    argv.push("--icon".to_string());
+                    argv.push(icon.clone());
+                }
+            }
+            _ => argv.push(expand_field_codes(&token, entry)),
+        }
+    }
+
+    if argv.is_empty() {
+        return Err(AppError::Process(format!(
+            "Desktop entry {} has an empty Exec line",
+            entry.file.display()
+        )));
+    }
+    Ok(argv)
+}
+
+/// Expand the field codes that may appear inside an argument
+/// Deprecated and unknown codes are removed.
+fn expand_field_codes(token: &str, entry: &DesktopEntry) -> String {
+    let mut result = String::with_capacity(token.len());
+    let mut chars = token.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('%') => result.push('%'),
+            Some('c') => result.push_str(&entry.name),
+            Some('k') => result.push_str(&entry.file.to_string_lossy()),
+            _ => {}
+        }
+    }
+    result
+}
+
+/// Split an `Exec=` value into arguments
+/// Arguments containing spaces are double-quoted; inside quotes a backslash
+/// escapes `"`, `` ` ``, `$` and `\`.
+fn split_exec(exec: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut started = false;
+    let mut quoted = false;
+    let mut chars = exec.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                started = true;
+            }
+            '\\' if quoted => {
+                if let Some(next) = chars.next() {
+                    current.push(next);
+                }
+            }
+            ' ' | '\t' if !quoted => {
+                if started {
+                    args.push(std::mem::take(&mut current));
+                    started = false;
+                }
+            }
+            _ => {
+                current.push(c);
+                started = true;
+            }
+        }
+    }
+    if started {
+        args.push(current);
+    }
+    args
+}
+
+/// Undo the escapes allowed in desktop entry string values
+fn unescape(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('s') => result.push(' '),
+            Some('n') => result.push('\n'),
+            Some('t') => result.push('\t'),
+            Some('r') => result.push('\r'),
+            Some('\\') => result.push('\\'),
+            // Other escapes belong to the Exec quoting rules
+            Some(other) => {
+                result.push('\\');
+                result.push(other);
+            }
+            None => result.push('\\'),
+        }
+    }
+    result
+}
+
+/// Desktop file id: the path below the applications directory with '/' as '-'
+fn desktop_file_id(dir: &Path, file: &Path) -> Option<String> {
+    let relative = file.strip_prefix(dir).ok()?.to_string_lossy().into_owned();
+    let id = relative.strip_suffix(".desktop")?;
+    Some(id.replace(['/', '\\'], "-"))
+}
+
+/// All `.desktop` files below a directory
+fn desktop_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return files;
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            files.extend(desktop_files(&path));
+        } else if path.extension().is_some_and(|ext| ext == "desktop") {
+            files.push(path);
+        }
+    }
+    files
+}
+
+/// XDG application directories, highest priority first
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn application_dirs() -> Vec<PathBuf> {
+    let mut data_dirs: Vec<PathBuf> = Vec::new();
+
+    match std::env::var_os("XDG_DATA_HOME").filter(|d| !d.is_empty()) {
+        Some(dir) => data_dirs.push(PathBuf::from(dir)),
+        None => data_dirs.extend(dirs::home_dir().map(|home| home.join(".local/share"))),
+    }
+
+    let system_dirs = std::env::var("XDG_DATA_DIRS")
+        .ok()
+        .filter(|d| !d.is_empty())
+        .unwrap_or_else(|| "/usr/local/share:/usr/share".to_string());
+    data_dirs.extend(system_dirs.split(':').map(PathBuf::from));
+
+    // Flatpak exports are missing from XDG_DATA_DIRS when not started from a session
+    data_dirs.extend(dirs::home_dir().map(|home| home.join(".local/share/flatpak/exports/share")));
+    data_dirs.push(PathBuf::from("/var/lib/flatpak/exports/share"));
+
+    let mut dirs: Vec<PathBuf> = Vec::new();
+    for dir in data_dirs.into_iter().map(|d| d.join("applications")) {
+        if dir.is_dir() && !dirs.contains(&dir) {
+            dirs.push(dir);
+        }
+    }
+    dirs
+}
+
+#[cfg(any(target_os = "windows", target_os = "macos"))]
+fn application_dirs() -> Vec<PathBuf> {
+    Vec::new()
+}
@@ -0,0 +1,160 @@
+//! Per-program environment variables
+//!
+//! The environment of a launched program is built in three layers:
+//! the parent environment (unless `inherit_env` is off), then the optional
+//! `.env` file, then the `env` map. Values may reference the parent
+//! environment as `${VAR}`.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::config::schema::{EnvValue, ProgramConfig};
+use crate::error::AppError;
+
+/// Apply the environment configured for `config` to `command`
+pub fn apply(command: &mut Command, config: &ProgramConfig) -> Result<(), AppError> {
+    if !config.inherit_env {
+        command.env_clear();
+    }
+
+    if let Some(env_file) = config.env_file.as_deref().filter(|f| !f.is_empty()) {
+        let path = resolve_env_file(env_file, config.working_directory.as_deref());
+        for (name, value) in load_env_file(&path)? {
+            command.env(name, value);
+        }
+    }
+
+    for (name, value) in &config.env {
+        match value {
+            EnvValue::Set { value } => {
+                command.env(name, expand(value));
+            }
+            EnvValue::Unset => {
+                command.env_remove(name);
+            }
+            EnvValue::Inherit => {
+                // Only has an effect when the rest of the environment is cleared
+                if let Ok(value) = std::env::var(name) {
+                    command.env(name, value);
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Check that a name can be used as an environment variable
+pub fn is_valid_name(name: &str) -> bool {
+    !name.is_empty() && !name.contains(['=', '\0'])
+}
+
+/// Expand `${VAR}` references from the parent environment
+/// Unknown variables expand to an empty string; `$$` is a literal `$`.
+pub fn expand(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut rest = value;
+
+    while let Some(index) = rest.find('$') {
+        result.push_str(&rest[..index]);
+        rest = &rest[index..];
+
+        if let Some(after) = rest.strip_prefix("$$") {
+            result.push('$');
+            rest = after;
+        } else if let Some(end) = rest.strip_prefix("${").and_then(|r| r.find('}')) {
+            let name = &rest[2..2 + end];
+            result.push_str(&std::env::var(name).unwrap_or_default());
+            rest = &rest[3 + end..];
+        } else {
+            result.push('$');
+            rest = &rest[1..];
+        }
+    }
+
+    result.push_str(rest);
+    result
+}
+
+/// Relative `.env` paths are resolved against the working directory
+fn resolve_env_file(env_file: &str, working_directory: Option<&str>) -> PathBuf {
+    let path = Path::new(env_file);
+    match working_directory.filter(|d| !d.is_empty()) {
+        Some(dir) if path.is_relative() => Path::new(dir).join(path),
+        _ => path.to_path_buf(),
+    }
+}
+
+/// Read a `.env` file
+///
+/// Supports `KEY=value` lines with an optional `export ` prefix, `#` comments,
+/// single-quoted values (taken literally) and double-quoted values (with `\n`,
+/// `\t`, `\"` and `\\` escapes and `${VAR}` expansion). Unquoted values are
+/// trimmed and expanded.
+fn load_env_file(path: &Path) -> Result<BTreeMap<String, String>, AppError> {
+    let content = std::fs::read_to_string(path).map_err(|e| {
+        AppError::Process(format!(
+            "Failed to read env file '{}': {}",
+            path.display(),
+            e
+        ))
+    })?;
+
+    let mut vars = BTreeMap::new();
+    for (number, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let (name, value) = line
+            .split_once('=')
+            .map(|(name, value)| (name.trim(), value.trim()))
+            .filter(|(name, _)| is_valid_name(name))
+            .ok_or_else(|| {
+                AppError::Process(format!(
+                    "Invalid line {} in env file '{}'",
+                    number + 1,
+                    path.display()
+                ))
+            })?;
+
+        vars.insert(name.to_string(), parse_value(value));
+    }
+
+    Ok(vars)
+}
+
+fn parse_value(value: &str) -> String {
+    if let Some(inner) = value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')) {
+        return inner.to_string();
+    }
+
+    if let Some(inner) = value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) {
+        let mut unescaped = String::with_capacity(inner.len());
+        let mut chars = inner.chars();
+        while let Some(c) = chars.next() {
+            if c != '\\' {
+                unescaped.push(c);
+                continue;
+            }
+            match chars.next() {
+                Some('n') => unescaped.push('\n'),
+                Some('t') => unescaped.push('\t'),
+                Some('r') => unescaped.push('\r'),
+                Some(other) => unescaped.push(other),
+                None => unescaped.push('\\'),
+            }
+        }
+        return expand(&unescaped);
+    }
+
+    // Unquoted values end at an inline comment
+    let value = match value.find(" #") {
+        Some(index) => value[..index].trim_end(),
+        None => value,
+    };
+    expand(value)
+}
@@ -0,0 +1,180 @@
+//! Per-hotkey logs of program output
+//!
+//! Output of programs with `log_output` enabled goes to
+//! `<config_dir>/logs/<hotkey-id>.log`. When a log grows past
+//! `MAX_LOG_BYTES` it is rotated to `.log.1`, `.log.2`, ..., both before a
+//! launch appends to it and while a running program writes to it.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+use chrono::Local;
+
+use crate::config::schema::ProgramConfig;
+use crate::error::AppError;
+
+use super::spawner::ProgramOutput;
+
+/// Size at which a log is rotated
+const MAX_LOG_BYTES: u64 = 1024 * 1024;
+
+/// Number of rotated logs kept next to the current one
+const ROTATED_LOGS: usize = 3;
+
+/// Most of a log returned for viewing
+const MAX_READ_BYTES: u64 = 64 * 1024;
+
+/// Lines of a log quoted in failure notifications
+const NOTIFICATION_TAIL_LINES: usize = 5;
+
+/// Path of the current log of a hotkey
+pub fn log_path(hotkey_id: &str) -> Result<PathBuf, AppError> {
+    // IDs are UUIDs, but imported configs may contain anything
+    let name: String = hotkey_id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+
+    Ok(crate::config::manager::get_config_dir()?
+        .join("logs")
+        .join(format!("{}.log", name)))
+}
+
+/// Writer to a hotkey's log that rotates it once it grows past `MAX_LOG_BYTES`
+/// Clones share the file, so a program's stdout and stderr can be copied into
+/// it from separate threads.
+#[derive(Clone)]
+pub struct LogWriter(Arc<Mutex<LogFile>>);
+
+struct LogFile {
+    path: PathBuf,
+    file: File,
+    size: u64,
+}
+
+impl Write for LogWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut log = self.0.lock().unwrap();
+        if log.size > MAX_LOG_BYTES {
+            rotate(&log.path);
+            log.file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&log.path)?;
+            log.size = 0;
+        }
+
+        let written = log.file.write(buf)?;
+        log.size += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.lock().unwrap().file.flush()
+    }
+}
+
+impl LogWriter {
+    /// Copy everything read from `pipe` into the log on a background thread
+    /// The thread ends when the pipe closes.
+    pub fn follow(&self, mut pipe: impl Read + Send + 'static) -> JoinHandle<()> {
+        let mut log = self.clone();
+        thread::spawn(move || {
+            let _ = io::copy(&mut pipe, &mut log);
+        })
+    }
+}
+
+/// Open the log of a hotkey for a new launch of `program`
+/// Rotates the log if needed and writes a header line for the launch.
+pub fn open(hotkey_id: &str, program: &ProgramConfig) -> Result<LogWriter, AppError> {
+    let path = log_path(hotkey_id)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    if fs::metadata(&path).is_ok_and(|m| m.len() > MAX_LOG_BYTES) {
+        rotate(&path);
+    }
+
+    let file = OpenOptions::new().create(true).append(true).open(&path)?;
+    let size = file.metadata()?.len();
+    let mut log = LogWriter(Arc::new(Mutex::new(LogFile { path, file, size })));
+    writeln!(
+        log,
+        "=== {} $ {} {}",
+        Local::now().format("%Y-%m-%d %H:%M:%S"),
+        program.path,
+        program.arguments.join(" ")
+    )?;
+
+    Ok(log)
+}
+
+/// Append the captured output of a program that was waited on
+pub fn append_output(
+    hotkey_id: &str,
+    program: &ProgramConfig,
+    output: &ProgramOutput,
+) -> Result<(), AppError> {
+    let mut file = open(hotkey_id, program)?;
+    file.write_all(output.stdout.as_bytes())?;
+    file.write_all(output.stderr.as_bytes())?;
+    writeln!(file, "=== exited with code {}", output.exit_code)?;
+    Ok(())
+}
+
+/// Read the end of the current log of a hotkey
+pub fn read(hotkey_id: &str) -> Result<String, AppError> {
+    let path = log_path(hotkey_id)?;
+    let mut file = match File::open(&path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(String::new()),
+        Err(e) => return Err(e.into()),
+    };
+
+    let len = file.metadata()?.len();
+    file.seek(SeekFrom::Start(len.saturating_sub(MAX_READ_BYTES)))?;
+
+    let mut buffer = Vec::new();
+    file.read_to_end(&mut buffer)?;
+    Ok(String::from_utf8_lossy(&buffer).into_owned())
+}
+
+/// Add the last lines of a hotkey's log to a failure message
+pub fn with_tail(hotkey_id: &str, message: &str) -> String {
+    let log = read(hotkey_id).unwrap_or_default();
+    let lines: Vec<&str> = log
+        .lines()
+        .filter(|line| !line.trim().is_empty() && !line.starts_with("=== "))
+        .collect();
+
+    if lines.is_empty() {
+        return message.to_string();
+    }
+
+    let tail = &lines[lines.len().saturating_sub(NOTIFICATION_TAIL_LINES)..];
+    format!("{}\n{}", message, tail.join("\n"))
+}
+
+/// Shift `name.log` to `name.log.1`, `name.log.1` to `name.log.2`, ...
+fn rotate(path: &std::path::Path) {
+    let rotated = |n: usize| PathBuf::from(format!("{}.{}", path.display(), n));
+
+    let _ = fs::remove_file(rotated(ROTATED_LOGS));
+    for n in (1..ROTATED_LOGS).rev() {
+        let _ = fs::rename(rotated(n), rotated(n + 1));
+    }
+    if let Err(e) = fs::rename(path, rotated(1)) {
+        eprintln!("Failed to rotate log {}: {}", path.display(), e);
+    }
+}
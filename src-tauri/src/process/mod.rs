@@ -1,4 +1,10 @@
 //! Process spawning module
 
+pub mod desktop;
+pub mod env;
+pub mod logs;
 pub mod platform;
+pub mod script;
 pub mod spawner;
+pub mod terminal;
+pub mod tracker;
@@ -3,6 +3,10 @@
 use std::path::Path;
 use std::process::Command;
 
+#[cfg(target_os = "linux")]
+use crate::config::schema::IoPriority;
+use crate::config::schema::ResourceLimits;
+
 // ============================================================================
 // Windows Implementation
 // ============================================================================
@@ -149,6 +153,211 @@ pub fn executable_extensions() -> Vec<&'static str> {
     vec!["", "sh", "AppImage"]
 }
 
+// ============================================================================
+// Process groups
+// ============================================================================
+
+/// Start the program in its own process group so it can be killed with its children
+#[cfg(unix)]
+pub fn configure_process_group(command: &mut Command) {
+    // A new session is also a new process group whose id is the child's pid
+    configure_detached(command);
+}
+
+#[cfg(target_os = "windows")]
+pub fn configure_process_group(_command: &mut Command) {
+    // taskkill /T walks the process tree from the pid, no group needed
+}
+
+/// Forcefully kill a process started with `configure_process_group` and its children
+#[cfg(unix)]
+pub fn kill_process_group(pid: u32) {
+    unsafe {
+        libc::kill(-(pid as libc::pid_t), libc::SIGKILL);
+    }
+}
+
+#[cfg(target_os = "windows")]
+pub fn kill_process_group(pid: u32) {
+    let mut command = Command::new("taskkill");
+    command.args(["/T", "/F", "/PID", &pid.to_string()]);
+    configure_hidden(&mut command);
+    let _ = command.output();
+}
+
+// ============================================================================
+// Resource limits
+// ============================================================================
+
+/// `ioprio_set` target: a single process
+#[cfg(target_os = "linux")]
+const IOPRIO_WHO_PROCESS: libc::c_int = 1;
+
+/// Bits of an IO priority value below the class
+#[cfg(target_os = "linux")]
+const IOPRIO_CLASS_SHIFT: libc::c_int = 13;
+
+/// Apply nice level, IO priority and rlimits in the child before it starts
+/// A limit that cannot be applied (e.g. a negative nice level without
+/// privileges) makes the launch fail rather than run unrestricted.
+#[cfg(target_os = "linux")]
+pub fn configure_resources(command: &mut Command, resources: &ResourceLimits) {
+    use std::io;
+    use std::os::unix::process::CommandExt;
+
+    const MIB: u64 = 1024 * 1024;
+
+    let nice = resources.nice;
+    let io_priority = resources.io_priority.map(|priority| match priority {
+        IoPriority::BestEffort { level } => (2 << IOPRIO_CLASS_SHIFT) | level.min(7) as libc::c_int,
+        IoPriority::Idle => 3 << IOPRIO_CLASS_SHIFT,
+    });
+    let rlimits = [
+        (
+            libc::RLIMIT_AS,
+            resources.memory_mb.map(|mb| u64::from(mb) * MIB),
+        ),
+        (libc::RLIMIT_CPU, resources.cpu_seconds.map(u64::from)),
+        (
+            libc::RLIMIT_FSIZE,
+            resources.file_size_mb.map(|mb| u64::from(mb) * MIB),
+        ),
+        (libc::RLIMIT_NOFILE, resources.open_files.map(u64::from)),
+    ];
+
+    if nice.is_none() && io_priority.is_none() && rlimits.iter().all(|(_, v)| v.is_none()) {
+        return;
+    }
+
+    // Only async-signal-safe calls between fork and exec
+    unsafe {
+        command.pre_exec(move || {
+            if let Some(nice) = nice {
+                if libc::setpriority(libc::PRIO_PROCESS, 0, nice) != 0 {
+                    return Err(io::Error::last_os_error());
+                }
+            }
+
+            if let Some(io_priority) = io_priority {
+                if libc::syscall(libc::SYS_ioprio_set, IOPRIO_WHO_PROCESS, 0, io_priority) != 0 {
+                    return Err(io::Error::last_os_error());
+                }
+            }
+
+            for (resource, value) in rlimits {
+                let Some(value) = value else {
+                    continue;
+                };
+                let mut limit = libc::rlimit {
+                    rlim_cur: 0,
+                    rlim_max: 0,
+                };
+                if libc::getrlimit(resource, &mut limit) != 0 {
+                    return Err(io::Error::last_os_error());
+                }
+                // The hard limit can only be lowered without privileges
+                let value = (value as libc::rlim_t).min(limit.rlim_max);
+                limit.rlim_cur = value;
+                limit.rlim_max = value;
+                if libc::setrlimit(resource, &limit) != 0 {
+                    return Err(io::Error::last_os_error());
+                }
+            }
+
+            Ok(())
+        });
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn configure_resources(_command: &mut Command, _resources: &ResourceLimits) {
+    // Resource limits are only supported on Linux
+}
+
+/// Find running processes started from `executable` (a canonical path)
+/// Matches the process image or, for scripts with a `#!` line, the script
+/// argument after the interpreter. Our own process is never included.
+#[cfg(target_os = "linux")]
+pub fn find_processes_by_executable(executable: &Path) -> Vec<u32> {
+    use std::io::Read;
+
+    let Ok(entries) = std::fs::read_dir("/proc") else {
+        return Vec::new();
+    };
+
+    let mut magic = [0u8; 2];
+    let is_script = std::fs::File::open(executable)
+        .and_then(|mut file| file.read_exact(&mut magic))
+        .is_ok_and(|_| &magic == b"#!");
+
+    let own_pid = std::process::id();
+    entries
+        .flatten()
+        .filter_map(|entry| entry.file_name().to_str()?.parse::<u32>().ok())
+        .filter(|&pid| pid != own_pid)
+        .filter(|&pid| {
+            let dir = Path::new("/proc").join(pid.to_string());
+            if std::fs::read_link(dir.join("exe")).is_ok_and(|exe| exe == executable) {
+                return true;
+            }
+
+            // Interpreted scripts show up as e.g. `/bin/sh /path/to/script`
+            is_script
+                && std::fs::read(dir.join("cmdline")).is_ok_and(|cmdline| {
+                    cmdline
+                        .split(|&b| b == 0)
+                        .nth(1)
+                        .and_then(|arg| std::str::from_utf8(arg).ok())
+                        .filter(|arg| !arg.is_empty())
+                        .is_some_and(|arg| {
+                            std::fs::canonicalize(arg).is_ok_and(|path| path == executable)
+                        })
+                })
+        })
+        .collect()
+}
+
+/// Find running processes of `executable` (a canonical path) with exactly
+/// `arguments` after the program name, e.g. `python3 server.py --port 8000`
+/// Our own process is never included.
+#[cfg(target_os = "linux")]
+pub fn find_processes_by_command(executable: &Path, arguments: &[String]) -> Vec<u32> {
+    let Ok(entries) = std::fs::read_dir("/proc") else {
+        return Vec::new();
+    };
+
+    let own_pid = std::process::id();
+    entries
+        .flatten()
+        .filter_map(|entry| entry.file_name().to_str()?.parse::<u32>().ok())
+        .filter(|&pid| pid != own_pid)
+        .filter(|&pid| {
+            let dir = Path::new("/proc").join(pid.to_string());
+            std::fs::read_link(dir.join("exe")).is_ok_and(|exe| exe == executable)
+                && std::fs::read(dir.join("cmdline")).is_ok_and(|cmdline| {
+                    // Arguments are NUL-terminated, so the last item is empty
+                    let mut items = cmdline.split(|&b| b == 0).skip(1);
+                    arguments
+                        .iter()
+                        .all(|argument| items.next() == Some(argument.as_bytes()))
+                        && items.next().is_none_or(|rest| rest.is_empty())
+                        && items.next().is_none()
+                })
+        })
+        .collect()
+}
+
+/// Only Linux exposes other processes' executables without extra permissions
+#[cfg(not(target_os = "linux"))]
+pub fn find_processes_by_executable(_executable: &Path) -> Vec<u32> {
+    Vec::new()
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn find_processes_by_command(_executable: &Path, _arguments: &[String]) -> Vec<u32> {
+    Vec::new()
+}
+
 // ============================================================================
 // Cross-platform utilities
 // ============================================================================
@@ -0,0 +1,123 @@
+//! Inline scripts stored in the config
+//!
+//! A script body is written to a fresh file in the temp directory, readable
+//! only by the current user, and run by its interpreter like any other
+//! program. The file is removed once the interpreter has exited.
+
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::config::schema::{ProgramConfig, ScriptConfig, ScriptInterpreter};
+use crate::error::AppError;
+
+/// Write a script to a temp file and build the program that runs it
+pub fn prepare(script: &ScriptConfig) -> Result<ProgramConfig, AppError> {
+    let path = write_temp_file(script)?;
+    let mut program = program(script, &path);
+    program.temp_file = Some(path);
+    Ok(program)
+}
+
+/// The program that runs a script saved at `path`
+pub fn program(script: &ScriptConfig, path: &Path) -> ProgramConfig {
+    let path = path.to_string_lossy().into_owned();
+    let arguments = match script.interpreter {
+        ScriptInterpreter::Pwsh => vec![
+            "-NoProfile".to_string(),
+            "-NonInteractive".to_string(),
+            "-File".to_string(),
+            path,
+        ],
+        _ => vec![path],
+    };
+
+    ProgramConfig {
+        path: script.interpreter.program().to_string(),
+        arguments,
+        working_directory: script.working_directory.clone(),
+        hidden: script.hidden,
+        timeout_ms: script.timeout_ms,
+        on_timeout: Default::default(),
+        inherit_env: true,
+        env_file: None,
+        env: script.env.clone(),
+        instance_policy: Default::default(),
+        log_output: false,
+        run_in_terminal: false,
+        keep_terminal_open: false,
+        resources: Default::default(),
+        temp_file: None,
+    }
+}
+
+/// Removes a program's temp file when dropped, unless released first
+/// Held while a launch is prepared, so the file doesn't outlive a launch
+/// that fails before the program is running.
+pub struct TempFileGuard<'a>(Option<&'a ProgramConfig>);
+
+impl<'a> TempFileGuard<'a> {
+    pub fn new(program: &'a ProgramConfig) -> Self {
+        Self(Some(program))
+    }
+
+    /// Keep the file; the running program's waiter removes it
+    pub fn release(mut self) {
+        self.0 = None;
+    }
+}
+
+impl Drop for TempFileGuard<'_> {
+    fn drop(&mut self) {
+        if let Some(program) = self.0 {
+            remove_temp_file(program);
+        }
+    }
+}
+
+/// Remove the temp file of a program once it has exited
+pub fn remove_temp_file(program: &ProgramConfig) {
+    if let Some(path) = &program.temp_file {
+        remove(path);
+    }
+}
+
+/// Remove a script file, ignoring one that is already gone
+pub fn remove(path: &Path) {
+    if let Err(e) = std::fs::remove_file(path) {
+        if e.kind() != std::io::ErrorKind::NotFound {
+            eprintln!("Failed to remove script {}: {}", path.display(), e);
+        }
+    }
+}
+
+/// Create the script file; `create_new` refuses to follow an existing path
+fn write_temp_file(script: &ScriptConfig) -> Result<PathBuf, AppError> {
+    let path = std::env::temp_dir().join(format!(
+        "global-hotkey-{}.{}",
+        uuid::Uuid::new_v4(),
+        script.interpreter.extension()
+    ));
+
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+
+    let mut file = options.open(&path).map_err(|e| {
+        AppError::Process(format!(
+            "Failed to create script file {}: {}",
+            path.display(),
+            e
+        ))
+    })?;
+    if let Err(e) = file.write_all(script.body.as_bytes()) {
+        remove(&path);
+        return Err(e.into());
+    }
+
+    Ok(path)
+}
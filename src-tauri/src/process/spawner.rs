@@ -1,12 +1,17 @@
 //! Program launching functionality
 
+use std::io::{self, BufRead, BufReader, Read, Write};
 use std::path::{Path, PathBuf};
-use std::process::Command;
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::Duration;
 
-use crate::config::schema::ProgramConfig;
+use crate::config::schema::{ProgramConfig, ResourceLimits, TimeoutOutcome};
 use crate::error::AppError;
 
-use super::platform;
+use super::{desktop, env, platform, script, terminal};
 
 /// Get additional PATH directories to search on macOS
 /// GUI apps don't inherit the shell's PATH, so we need to check common locations
@@ -49,7 +54,37 @@ fn find_in_extra_paths(name: &str) -> Option<PathBuf> {
 }
 
 /// Launch a program with the given configuration
-pub fn launch(config: &ProgramConfig) -> Result<(), AppError> {
+/// Returns the detached child, which callers may track or drop.
+pub fn launch(config: &ProgramConfig) -> Result<Child, AppError> {
+    launch_with_output(config, false)
+}
+
+/// Launch a program, piping its stdout and stderr to us if `piped` is set
+/// Programs with `run_in_terminal` are started inside a terminal emulator.
+pub fn launch_with_output(config: &ProgramConfig, piped: bool) -> Result<Child, AppError> {
+    let guard = script::TempFileGuard::new(config);
+    let expanded = desktop::expand(config)?;
+    let wrapped = terminal::wrap(expanded.as_ref())?;
+    let child = spawn_detached(&wrapped, piped)?;
+    guard.release();
+    Ok(child)
+}
+
+/// Launch a program with its stdout and stderr piped to us, for reading
+/// Never started inside a terminal, since its output is read here.
+pub fn launch_piped(config: &ProgramConfig) -> Result<Child, AppError> {
+    let guard = script::TempFileGuard::new(config);
+    let expanded = desktop::expand(config)?;
+    let child = spawn_detached(expanded.as_ref(), true)?;
+    guard.release();
+    Ok(child)
+}
+
+/// Spawn a detached program, optionally with its stdout and stderr piped
+fn spawn_detached(config: &ProgramConfig, piped: bool) -> Result<Child, AppError> {
+    let wrapped = wrap_in_scope(config)?;
+    let config = &wrapped;
+
     // Resolve the program path - check direct path first, then PATH
     let resolved_path = resolve_program(&config.path)
         .ok_or_else(|| AppError::Process(format!("Program not found: {}", config.path)))?;
@@ -59,12 +94,8 @@ pub fn launch(config: &ProgramConfig) -> Result<(), AppError> {
     // Build the command
     let mut command = Command::new(&resolved_path);
 
-    // Add arguments
-    for arg in &config.arguments {
-        if !arg.is_empty() {
-            command.arg(arg);
-        }
-    }
+    // Arguments were filtered before expansion; empty ones are intentional
+    command.args(&config.arguments);
 
     // Set working directory
     if let Some(ref working_dir) = config.working_directory {
@@ -81,25 +112,383 @@ pub fn launch(config: &ProgramConfig) -> Result<(), AppError> {
         }
     }
 
+    env::apply(&mut command, config)?;
+
     // Apply platform-specific settings for hidden mode
     if config.hidden {
         platform::configure_hidden(&mut command);
     }
 
+    platform::configure_resources(&mut command, &config.resources);
+
+    // Overrides the null stdio of hidden mode
+    if piped {
+        command.stdout(Stdio::piped()).stderr(Stdio::piped());
+    }
+
     // Detach the process from our process group
     platform::configure_detached(&mut command);
 
     // Spawn the process (don't wait for it)
     command.spawn().map_err(|e| {
         AppError::Process(format!("Failed to launch program '{}': {}", config.path, e))
+    })
+}
+
+/// Run a program in a transient systemd user scope if it asks for one
+/// systemd-run execs the program itself, so the PID and stdio stay the same.
+fn wrap_in_scope(config: &ProgramConfig) -> Result<ProgramConfig, AppError> {
+    if !config.resources.systemd_scope || !cfg!(target_os = "linux") {
+        return Ok(config.clone());
+    }
+
+    let systemd_run = resolve_program("systemd-run").ok_or_else(|| {
+        AppError::Process("systemd-run not found, cannot start a systemd scope".into())
+    })?;
+
+    let arguments = [
+        "--user",
+        "--scope",
+        "--quiet",
+        "--collect",
+        &format!("--description={}", config.path),
+        "--",
+        &config.path,
+    ]
+    .into_iter()
+    .map(String::from)
+    .chain(config.arguments.iter().cloned())
+    .collect();
+
+    Ok(ProgramConfig {
+        path: systemd_run,
+        arguments,
+        resources: ResourceLimits {
+            systemd_scope: false,
+            ..config.resources
+        },
+        ..config.clone()
+    })
+}
+
+/// Result of a program that was waited on
+#[derive(Debug, Clone)]
+pub struct ProgramOutput {
+    pub exit_code: i32,
+    /// Captured standard output (lossily decoded as UTF-8)
+    pub stdout: String,
+    /// Captured standard error (lossily decoded as UTF-8)
+    pub stderr: String,
+    /// Set when the program was killed after its timeout; output is partial
+    pub timed_out: bool,
+}
+
+/// Longest stretch of partial output quoted in a timeout error
+const MAX_PARTIAL_OUTPUT_CHARS: usize = 500;
+
+/// Most of stdout and of stderr kept by `launch_and_capture`
+const MAX_CAPTURED_BYTES: usize = 64 * 1024;
+
+/// What happens to the output of a program that is waited on
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum OutputMode {
+    /// Leave stdout and stderr as they are; nothing is captured
+    Inherit,
+    /// Keep stdout up to the given number of bytes and the tail of stderr
+    /// A program that prints more is killed; the returned stdout is then one
+    /// byte over the limit.
+    Limit(usize),
+    /// Keep the last `MAX_CAPTURED_BYTES` of stdout and of stderr
+    Tail,
+    /// Pass stdout lines to the callback without keeping them; stderr is discarded
+    Stream,
+}
+
+/// Launch a program and wait for it to exit, returning its exit code
+/// The process is not detached so it can be waited on. Its output is not
+/// captured, so the returned stdout and stderr are empty.
+pub fn launch_and_wait(config: &ProgramConfig) -> Result<ProgramOutput, AppError> {
+    run_captured(config, None, OutputMode::Inherit, |_| {})
+}
+
+/// Launch a program and wait for it to exit, capturing the last
+/// `MAX_CAPTURED_BYTES` of its stdout and stderr
+pub fn launch_and_capture(config: &ProgramConfig) -> Result<ProgramOutput, AppError> {
+    run_captured(config, None, OutputMode::Tail, |_| {})
+}
+
+/// Launch a program and wait for it to exit, calling `on_line` for each line of stdout
+/// as it is printed. Output is not kept, so the returned stdout and stderr are empty;
+/// this suits long-running programs.
+/// If `timeout_ms` is set and expires, the program and its children are killed and
+/// the result follows `on_timeout`.
+pub fn launch_and_stream(
+    config: &ProgramConfig,
+    on_line: impl FnMut(&str),
+) -> Result<ProgramOutput, AppError> {
+    run_captured(config, None, OutputMode::Stream, on_line)
+}
+
+/// Launch a program with `input` written to its stdin and wait for it to exit,
+/// capturing its output. Timeouts behave as in `launch_and_stream`.
+/// A program whose stdout grows past `max_output_bytes` is killed; callers
+/// detect this by stdout being longer than the limit.
+pub fn launch_with_input(
+    config: &ProgramConfig,
+    input: &str,
+    max_output_bytes: usize,
+) -> Result<ProgramOutput, AppError> {
+    run_captured(
+        config,
+        Some(input.as_bytes().to_vec()),
+        OutputMode::Limit(max_output_bytes),
+        |_| {},
+    )
+}
+
+/// Run a program to completion, then remove its temp file if it has one
+fn run_captured(
+    config: &ProgramConfig,
+    input: Option<Vec<u8>>,
+    mode: OutputMode,
+    on_line: impl FnMut(&str),
+) -> Result<ProgramOutput, AppError> {
+    let result = capture(config, input, mode, on_line);
+    script::remove_temp_file(config);
+    result
+}
+
+fn capture(
+    config: &ProgramConfig,
+    input: Option<Vec<u8>>,
+    mode: OutputMode,
+    mut on_line: impl FnMut(&str),
+) -> Result<ProgramOutput, AppError> {
+    let expanded = desktop::expand(config)?;
+    let wrapped = wrap_in_scope(expanded.as_ref())?;
+    let config = &wrapped;
+
+    let resolved_path = resolve_program(&config.path)
+        .ok_or_else(|| AppError::Process(format!("Program not found: {}", config.path)))?;
+
+    let mut command = Command::new(&resolved_path);
+
+    // Arguments were filtered before expansion; empty ones are intentional
+    command.args(&config.arguments);
+
+    // Set working directory
+    if let Some(ref working_dir) = config.working_directory {
+        if !working_dir.is_empty() {
+            let dir = std::path::Path::new(working_dir);
+            if dir.exists() && dir.is_dir() {
+                command.current_dir(dir);
+            }
+        }
+    }
+
+    env::apply(&mut command, config)?;
+
+    // Apply hidden mode if configured
+    if config.hidden {
+        platform::configure_hidden(&mut command);
+    }
+
+    platform::configure_resources(&mut command, &config.resources);
+
+    command.stdin(if input.is_some() {
+        Stdio::piped()
+    } else {
+        Stdio::null()
+    });
+
+    // Capture output even in hidden mode so later steps can use it
+    if mode != OutputMode::Inherit {
+        command.stdout(Stdio::piped()).stderr(Stdio::piped());
+    }
+
+    // NOTE: Don't detach - we need to wait for this process. A timeout or output
+    // limit still needs its own process group so children that inherited the
+    // pipes are killed too.
+    if config.timeout_ms.is_some() || matches!(mode, OutputMode::Limit(_)) {
+        platform::configure_process_group(&mut command);
+    }
+
+    let mut child = command.spawn().map_err(|e| {
+        AppError::Process(format!("Failed to launch program '{}': {}", config.path, e))
+    })?;
+
+    // Killing the group closes the pipes, which ends the reads below
+    let timed_out = Arc::new(AtomicBool::new(false));
+    let watchdog = config.timeout_ms.map(|timeout_ms| {
+        let (done_tx, done_rx) = mpsc::channel::<()>();
+        let timed_out = timed_out.clone();
+        let pid = child.id();
+        let handle = thread::spawn(move || {
+            if let Err(mpsc::RecvTimeoutError::Timeout) =
+                done_rx.recv_timeout(Duration::from_millis(timeout_ms))
+            {
+                timed_out.store(true, Ordering::SeqCst);
+                platform::kill_process_group(pid);
+            }
+        });
+        (done_tx, handle)
+    });
+
+    // Feed stdin on its own thread; dropping the pipe afterwards signals EOF.
+    // Programs that exit without reading all of it cause a broken pipe, which is fine.
+    let stdin_writer = input.zip(child.stdin.take()).map(|(input, mut stdin)| {
+        thread::spawn(move || {
+            let _ = stdin.write_all(&input);
+        })
+    });
+
+    // Drain stderr on its own thread so a full pipe can't block the child
+    let stderr_reader = child.stderr.take().map(|mut stderr| {
+        thread::spawn(move || {
+            let mut buffer = Vec::new();
+            match mode {
+                OutputMode::Stream => {
+                    let _ = io::copy(&mut stderr, &mut io::sink());
+                }
+                OutputMode::Tail | OutputMode::Limit(_) => {
+                    let mut chunk = [0u8; 8192];
+                    while let Ok(n) = stderr.read(&mut chunk) {
+                        if n == 0 {
+                            break;
+                        }
+                        buffer.extend_from_slice(&chunk[..n]);
+                        if buffer.len() > 2 * MAX_CAPTURED_BYTES {
+                            trim_to_tail(&mut buffer, MAX_CAPTURED_BYTES);
+                        }
+                    }
+                    trim_to_tail(&mut buffer, MAX_CAPTURED_BYTES);
+                }
+                OutputMode::Inherit => {}
+            }
+            String::from_utf8_lossy(&buffer).into_owned()
+        })
+    });
+
+    let mut stdout = Vec::new();
+    match (mode, child.stdout.take()) {
+        (OutputMode::Limit(limit), Some(pipe)) => {
+            // Read one byte past the limit to tell "exactly at" from "over" it
+            let _ = pipe.take(limit as u64 + 1).read_to_end(&mut stdout);
+            if stdout.len() > limit {
+                platform::kill_process_group(child.id());
+            }
+        }
+        (_, Some(pipe)) => {
+            let mut reader = BufReader::new(pipe);
+            let mut line = Vec::new();
+            while reader.read_until(b'\n', &mut line).unwrap_or(0) > 0 {
+                on_line(String::from_utf8_lossy(&line).trim_end_matches(['\r', '\n']));
+                if mode == OutputMode::Tail {
+                    stdout.extend_from_slice(&line);
+                    // Trimming only past twice the limit keeps this cheap per line
+                    if stdout.len() > 2 * MAX_CAPTURED_BYTES {
+                        trim_to_tail(&mut stdout, MAX_CAPTURED_BYTES);
+                    }
+                }
+                line.clear();
+            }
+        }
+        (_, None) => {}
+    }
+    if mode == OutputMode::Tail {
+        trim_to_tail(&mut stdout, MAX_CAPTURED_BYTES);
+    }
+    let stdout = String::from_utf8_lossy(&stdout).into_owned();
+
+    let status = child.wait();
+
+    if let Some(handle) = stdin_writer {
+        let _ = handle.join();
+    }
+
+    if let Some((done_tx, handle)) = watchdog {
+        let _ = done_tx.send(());
+        let _ = handle.join();
+    }
+
+    let status = status.map_err(|e| {
+        AppError::Process(format!(
+            "Failed to wait for program '{}': {}",
+            config.path, e
+        ))
     })?;
 
-    Ok(())
+    let stderr = stderr_reader
+        .and_then(|handle| handle.join().ok())
+        .unwrap_or_default();
+
+    if !timed_out.load(Ordering::SeqCst) {
+        return Ok(ProgramOutput {
+            exit_code: status.code().unwrap_or(-1),
+            stdout,
+            stderr,
+            timed_out: false,
+        });
+    }
+
+    let timeout_ms = config.timeout_ms.unwrap_or_default();
+    eprintln!(
+        "Program '{}' timed out after {} ms and was killed",
+        config.path, timeout_ms
+    );
+
+    match config.on_timeout {
+        TimeoutOutcome::Fail => Err(AppError::Process(format!(
+            "Program '{}' timed out after {} ms and was killed{}",
+            config.path,
+            timeout_ms,
+            describe_partial_output(&stdout, &stderr)
+        ))),
+        TimeoutOutcome::ExitCode { code } => Ok(ProgramOutput {
+            exit_code: code,
+            stdout,
+            stderr,
+            timed_out: true,
+        }),
+    }
+}
+
+/// Drop the start of `buffer` so at most `limit` bytes remain
+fn trim_to_tail(buffer: &mut Vec<u8>, limit: usize) {
+    if buffer.len() > limit {
+        buffer.drain(..buffer.len() - limit);
+    }
+}
+
+/// Describe the tail of the output captured before a timeout, for error messages
+fn describe_partial_output(stdout: &str, stderr: &str) -> String {
+    let output = if stderr.trim().is_empty() {
+        stdout.trim()
+    } else {
+        stderr.trim()
+    };
+
+    if output.is_empty() {
+        return String::new();
+    }
+
+    let count = output.chars().count();
+    let tail: String = output
+        .chars()
+        .skip(count.saturating_sub(MAX_PARTIAL_OUTPUT_CHARS))
+        .collect();
+    format!("; last output: {}", tail)
 }
 
 /// Validate that a path exists and points to an executable
-/// Also checks if the program is available in PATH or common directories
+/// Also checks if the program is available in PATH or common directories, or
+/// names a desktop entry
 pub fn validate_path(path: &str) -> bool {
+    // Desktop entries and application ids
+    if desktop::is_desktop_entry(path) {
+        return true;
+    }
+
     // First check if it's a direct path that exists
     let p = Path::new(path);
     if p.exists() {
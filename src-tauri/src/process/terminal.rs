@@ -0,0 +1,156 @@
+//! Running command-line programs inside a terminal emulator
+//!
+//! A program with `run_in_terminal` is launched as the argument of a terminal
+//! emulator: the one set in the app settings, or the first one found on
+//! `PATH`. Each terminal has its own convention for passing the command.
+
+use std::path::Path;
+
+use crate::config::schema::ProgramConfig;
+use crate::error::AppError;
+
+use super::spawner;
+
+/// Terminals tried in order when none is configured
+const KNOWN_TERMINALS: &[&str] = &[
+    "x-terminal-emulator",
+    "gnome-terminal",
+    "konsole",
+    "kitty",
+    "alacritty",
+    "wezterm",
+    "xterm",
+];
+
+/// Shell snippet that runs its arguments and waits for Enter afterwards
+const KEEP_OPEN_SCRIPT: &str = r#""$@"; code=$?; printf '\n[Process exited with code %s. Press Enter to close.]' "$code"; read -r _"#;
+
+/// Argument conventions of a terminal emulator
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Flavor {
+    /// `gnome-terminal [--working-directory=DIR] -- CMD ARGS...`
+    Gnome,
+    /// `konsole [--workdir DIR] -e CMD ARGS...`
+    Konsole,
+    /// `kitty [--directory DIR] CMD ARGS...`
+    Kitty,
+    /// `alacritty [--working-directory DIR] -e CMD ARGS...`
+    Alacritty,
+    /// `wezterm start [--cwd DIR] -- CMD ARGS...`
+    Wezterm,
+    /// `TERM -e CMD ARGS...` (x-terminal-emulator, xterm and others)
+    Generic,
+}
+
+impl Flavor {
+    fn of(terminal: &str) -> Self {
+        let name = Path::new(terminal)
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+
+        match name.as_str() {
+            "gnome-terminal" => Flavor::Gnome,
+            "konsole" => Flavor::Konsole,
+            "kitty" => Flavor::Kitty,
+            "alacritty" => Flavor::Alacritty,
+            "wezterm" => Flavor::Wezterm,
+            _ => Flavor::Generic,
+        }
+    }
+}
+
+/// Wrap a program in a terminal emulator if it asks to run in one
+/// Hidden programs and platforms without X11/Wayland terminals are returned unchanged.
+pub fn wrap(config: &ProgramConfig) -> Result<ProgramConfig, AppError> {
+    if !config.run_in_terminal || config.hidden || cfg!(any(windows, target_os = "macos")) {
+        return Ok(config.clone());
+    }
+
+    let terminal = find_terminal()?;
+
+    let mut command: Vec<String> = std::iter::once(config.path.clone())
+        .chain(config.arguments.iter().cloned())
+        .collect();
+    if config.keep_terminal_open {
+        command.splice(
+            0..0,
+            [
+                "sh".to_string(),
+                "-c".to_string(),
+                KEEP_OPEN_SCRIPT.to_string(),
+                "sh".to_string(),
+            ],
+        );
+    }
+
+    let working_directory = config
+        .working_directory
+        .as_deref()
+        .filter(|d| !d.is_empty());
+    let mut arguments = Vec::new();
+    match Flavor::of(&terminal) {
+        Flavor::Gnome => {
+            if let Some(dir) = working_directory {
+                arguments.push(format!("--working-directory={}", dir));
+            }
+            arguments.push("--".to_string());
+        }
+        Flavor::Konsole => {
+            if let Some(dir) = working_directory {
+                arguments.extend(["--workdir".to_string(), dir.to_string()]);
+            }
+            arguments.push("-e".to_string());
+        }
+        Flavor::Kitty => {
+            if let Some(dir) = working_directory {
+                arguments.extend(["--directory".to_string(), dir.to_string()]);
+            }
+        }
+        Flavor::Alacritty => {
+            if let Some(dir) = working_directory {
+                arguments.extend(["--working-directory".to_string(), dir.to_string()]);
+            }
+            arguments.push("-e".to_string());
+        }
+        Flavor::Wezterm => {
+            arguments.push("start".to_string());
+            if let Some(dir) = working_directory {
+                arguments.extend(["--cwd".to_string(), dir.to_string()]);
+            }
+            arguments.push("--".to_string());
+        }
+        Flavor::Generic => arguments.push("-e".to_string()),
+    }
+    arguments.extend(command);
+
+    Ok(ProgramConfig {
+        path: terminal,
+        arguments,
+        run_in_terminal: false,
+        ..config.clone()
+    })
+}
+
+/// The configured terminal, or the first known one on PATH
+fn find_terminal() -> Result<String, AppError> {
+    let configured = crate::config::manager::cached_settings()
+        .terminal
+        .filter(|t| !t.trim().is_empty());
+
+    if let Some(terminal) = configured {
+        return spawner::resolve_program(&terminal).ok_or_else(|| {
+            AppError::Process(format!("Configured terminal not found: {}", terminal))
+        });
+    }
+
+    KNOWN_TERMINALS
+        .iter()
+        .find_map(|name| spawner::resolve_program(name))
+        .ok_or_else(|| {
+            AppError::Process(format!(
+                "No terminal emulator found (tried {})",
+                KNOWN_TERMINALS.join(", ")
+            ))
+        })
+}
@@ -0,0 +1,256 @@
+//! Table of programs launched by hotkeys
+//!
+//! Detached launches are registered with the hotkey that started them. A
+//! waiter thread per process reaps it when it exits and records the exit
+//! code, so the table always reflects what is still running.
+
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::process::Child;
+use std::sync::Mutex;
+use std::thread::{self, JoinHandle};
+
+use chrono::Utc;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+
+use crate::config::schema::ProgramConfig;
+use crate::error::AppError;
+use crate::tray::{emit_event, refresh_menu, send_notification};
+
+use super::{desktop, logs, platform, script, spawner};
+
+/// Number of finished processes kept in the table
+const MAX_FINISHED: usize = 20;
+
+/// Lifecycle of a tracked process
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum ProcessStatus {
+    Running,
+    /// Exited on its own (no code if it was ended by a signal)
+    Exited {
+        code: Option<i32>,
+    },
+    /// Killed from the app
+    Killed,
+}
+
+/// A program launched by a hotkey
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrackedProcess {
+    pub pid: u32,
+    pub hotkey_id: String,
+    pub hotkey_name: String,
+    /// Program and arguments, for display
+    pub command: String,
+    /// Program path as configured, to find other launches of the same program
+    #[serde(skip)]
+    path: String,
+    /// Arguments as configured, to tell apart launches of the same interpreter
+    #[serde(skip)]
+    arguments: Vec<String>,
+    /// Output goes to the hotkey's log
+    pub logged: bool,
+    pub started_at: String,
+    pub status: ProcessStatus,
+}
+
+impl TrackedProcess {
+    pub fn is_running(&self) -> bool {
+        matches!(self.status, ProcessStatus::Running)
+    }
+}
+
+/// Tracked processes, oldest first
+static TABLE: Lazy<Mutex<VecDeque<TrackedProcess>>> = Lazy::new(|| Mutex::new(VecDeque::new()));
+
+/// Register a launched process and reap it in the background
+/// Its temp file, if any, is removed once it exits.
+/// A logged process that fails is reported with the end of its log.
+/// `readers` are the threads copying its output to the log; they are joined
+/// before the log is quoted.
+pub fn track(
+    hotkey_id: &str,
+    hotkey_name: &str,
+    program: &ProgramConfig,
+    mut child: Child,
+    readers: Vec<JoinHandle<()>>,
+) {
+    let pid = child.id();
+    let command = std::iter::once(program.path.as_str())
+        .chain(program.arguments.iter().map(|a| a.as_str()))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    TABLE.lock().unwrap().push_back(TrackedProcess {
+        pid,
+        hotkey_id: hotkey_id.to_string(),
+        hotkey_name: hotkey_name.to_string(),
+        command,
+        path: program.path.clone(),
+        arguments: program.arguments.clone(),
+        logged: program.log_output,
+        started_at: Utc::now().to_rfc3339(),
+        status: ProcessStatus::Running,
+    });
+    changed();
+
+    let hotkey_id = hotkey_id.to_string();
+    let hotkey_name = hotkey_name.to_string();
+    let logged = program.log_output;
+    let temp_file = program.temp_file.clone();
+    thread::spawn(move || {
+        let code = child.wait().ok().and_then(|status| status.code());
+        for reader in readers {
+            let _ = reader.join();
+        }
+        if let Some(path) = temp_file {
+            script::remove(&path);
+        }
+        let exited = set_status(pid, ProcessStatus::Exited { code });
+
+        // Killed processes are not failures
+        if exited && logged && code != Some(0) {
+            let message = match code {
+                Some(code) => format!("'{}' exited with code {}", hotkey_name, code),
+                None => format!("'{}' was terminated by a signal", hotkey_name),
+            };
+            send_notification("Program Failed", &logs::with_tail(&hotkey_id, &message));
+        }
+    });
+}
+
+/// Get all tracked processes, oldest first
+pub fn list() -> Vec<TrackedProcess> {
+    TABLE.lock().unwrap().iter().cloned().collect()
+}
+
+/// Get the running processes started by a hotkey
+pub fn running_for(hotkey_id: &str) -> Vec<TrackedProcess> {
+    TABLE
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|p| p.hotkey_id == hotkey_id && p.is_running())
+        .cloned()
+        .collect()
+}
+
+/// Kill a running process together with its children
+pub fn kill(pid: u32) -> Result<(), AppError> {
+    let running = TABLE
+        .lock()
+        .unwrap()
+        .iter()
+        .any(|p| p.pid == pid && p.is_running());
+    if !running {
+        return Err(AppError::Process(format!(
+            "No running process with PID {}",
+            pid
+        )));
+    }
+
+    // Detached launches lead their own process group
+    platform::kill_process_group(pid);
+    set_status(pid, ProcessStatus::Killed);
+    Ok(())
+}
+
+/// Running tracked launches of a program, by any hotkey
+/// Launches of an interpreter only count with the same arguments.
+pub fn find_tracked(program: &ProgramConfig) -> Vec<u32> {
+    let interpreted = resolve(program).is_some_and(|(executable, _)| is_interpreter(&executable));
+    TABLE
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|p| p.is_running() && p.path == program.path)
+        .filter(|p| !interpreted || p.arguments == program.arguments)
+        .map(|p| p.pid)
+        .collect()
+}
+
+/// Find running copies of a program
+/// Includes tracked launches and, on Linux, untracked processes with the same
+/// executable, or the same command line for interpreters. Only used to detect
+/// a running copy; untracked processes are never stopped.
+pub fn find_instances(program: &ProgramConfig) -> Vec<u32> {
+    let mut pids = find_tracked(program);
+
+    if let Some((executable, arguments)) = resolve(program) {
+        let found = if is_interpreter(&executable) {
+            platform::find_processes_by_command(&executable, &arguments)
+        } else {
+            platform::find_processes_by_executable(&executable)
+        };
+        for pid in found {
+            if !pids.contains(&pid) {
+                pids.push(pid);
+            }
+        }
+    }
+
+    pids
+}
+
+/// Canonical executable and arguments a program runs with
+/// Desktop entries resolve to the program their Exec line runs.
+fn resolve(program: &ProgramConfig) -> Option<(PathBuf, Vec<String>)> {
+    let program = desktop::expand(program).ok()?;
+    let path = spawner::resolve_program(&program.path)?;
+    let executable = std::fs::canonicalize(path).ok()?;
+    Some((executable, program.into_owned().arguments))
+}
+
+/// Whether an executable is a shell or script interpreter, which runs many
+/// unrelated programs under the same image (e.g. `python3.12`, `bash`)
+fn is_interpreter(executable: &Path) -> bool {
+    const INTERPRETERS: &[&str] = &[
+        "sh", "bash", "dash", "zsh", "ksh", "fish", "python", "pypy", "node", "nodejs", "deno",
+        "bun", "pwsh", "perl", "ruby", "php", "lua", "tclsh",
+    ];
+
+    executable
+        .file_name()
+        .and_then(|name| name.to_str())
+        .map(|name| name.trim_end_matches(".exe"))
+        .map(|name| name.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.'))
+        .is_some_and(|name| INTERPRETERS.contains(&name))
+}
+
+/// Stop a tracked process with its children
+/// A process that exited in the meantime is not an error.
+pub fn stop(pid: u32) {
+    let _ = kill(pid);
+}
+
+/// Update the status of the running entry for `pid`
+/// A process that was killed stays `Killed` when its waiter sees it exit.
+/// Returns false if there was no running entry.
+fn set_status(pid: u32, status: ProcessStatus) -> bool {
+    {
+        let mut table = TABLE.lock().unwrap();
+        let Some(entry) = table.iter_mut().find(|p| p.pid == pid && p.is_running()) else {
+            return false;
+        };
+        entry.status = status;
+
+        // Drop the oldest finished entries beyond the limit
+        let finished = table.iter().filter(|p| !p.is_running()).count();
+        for _ in MAX_FINISHED..finished {
+            if let Some(index) = table.iter().position(|p| !p.is_running()) {
+                table.remove(index);
+            }
+        }
+    }
+    changed();
+    true
+}
+
+/// Tell the frontend and the tray menu that the table changed
+fn changed() {
+    emit_event("processes-changed", list());
+    refresh_menu();
+}
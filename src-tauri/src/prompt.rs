@@ -0,0 +1,101 @@
+//! Small input dialog for `{prompt:Label}` placeholders
+//!
+//! Each prompt opens its own always-on-top window with the `#prompt/<id>`
+//! route. The window reads its label with `get_prompt` and answers with
+//! `answer_prompt`; closing the window cancels the prompt.
+
+use std::collections::HashMap;
+use std::sync::mpsc::{self, Sender};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use once_cell::sync::Lazy;
+use tauri::{Manager, WebviewUrl, WebviewWindowBuilder, WindowEvent};
+
+use crate::error::AppError;
+use crate::tray::APP_HANDLE;
+
+/// Prompts left unanswered this long are cancelled
+const PROMPT_TIMEOUT: Duration = Duration::from_secs(300);
+
+struct PendingPrompt {
+    label: String,
+    answer: Sender<Option<String>>,
+}
+
+static PENDING: Lazy<Mutex<HashMap<String, PendingPrompt>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Ask the user for a value and block until it is entered
+/// Returns an error if the prompt is cancelled, times out or cannot be shown.
+pub fn ask(label: &str) -> Result<String, AppError> {
+    let app = APP_HANDLE
+        .read()
+        .unwrap()
+        .clone()
+        .ok_or_else(|| AppError::Prompt("No window available to ask for input".into()))?;
+
+    let id = uuid::Uuid::new_v4().to_string();
+    let window_label = format!("prompt-{}", id);
+    let (tx, rx) = mpsc::channel();
+    PENDING.lock().unwrap().insert(
+        id.clone(),
+        PendingPrompt {
+            label: label.to_string(),
+            answer: tx,
+        },
+    );
+
+    let window = WebviewWindowBuilder::new(
+        &app,
+        &window_label,
+        WebviewUrl::App(format!("index.html#prompt/{}", id).into()),
+    )
+    .title(label)
+    .inner_size(360.0, 140.0)
+    .resizable(false)
+    .always_on_top(true)
+    .center()
+    .focused(true)
+    .build()
+    .map_err(|e| {
+        PENDING.lock().unwrap().remove(&id);
+        AppError::Prompt(format!("Failed to open prompt window: {}", e))
+    })?;
+
+    let closed_id = id.clone();
+    window.on_window_event(move |event| {
+        if let WindowEvent::CloseRequested { .. } = event {
+            answer(&closed_id, None);
+        }
+    });
+
+    let result = rx.recv_timeout(PROMPT_TIMEOUT);
+
+    PENDING.lock().unwrap().remove(&id);
+    // The app hides windows on close requests, so destroy it instead
+    if let Some(window) = app.get_webview_window(&window_label) {
+        let _ = window.destroy();
+    }
+
+    match result {
+        Ok(Some(value)) => Ok(value),
+        Ok(None) => Err(AppError::Prompt(format!(
+            "Prompt '{}' was cancelled",
+            label
+        ))),
+        Err(_) => Err(AppError::Prompt(format!("Prompt '{}' timed out", label))),
+    }
+}
+
+/// Get the label of a pending prompt
+pub fn label(id: &str) -> Option<String> {
+    PENDING.lock().unwrap().get(id).map(|p| p.label.clone())
+}
+
+/// Answer a pending prompt; `None` cancels it
+pub fn answer(id: &str, value: Option<String>) {
+    if let Some(prompt) = PENDING.lock().unwrap().remove(id) {
+        let _ = prompt.answer.send(value);
+    }
+}
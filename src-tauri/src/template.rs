@@ -0,0 +1,168 @@
+//! Placeholder expansion for text typed or passed on by actions
+//!
+//! Placeholders have the form `{name}` or `{name:argument}`. Unknown
+//! placeholders are left in the text unchanged.
+
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use chrono::Local;
+
+use crate::backend::Backends;
+use crate::config::schema::ProgramConfig;
+use crate::error::AppError;
+
+/// Values read once per expansion, so repeated placeholders agree
+#[derive(Default)]
+struct Cache {
+    clipboard: Option<String>,
+    primary_selection: Option<String>,
+    answers: HashMap<String, String>,
+}
+
+/// Values available to placeholders
+#[derive(Debug, Clone, Default)]
+pub struct TemplateContext {
+    /// Name of the hotkey that triggered the action
+    pub hotkey_name: Option<String>,
+    /// Response of the most recent AI call
+    pub ai_response: Option<String>,
+    /// Exit code of the most recent program that was waited on
+    pub exit_code: Option<i32>,
+    /// Output of the previous step (program stdout or AI response)
+    pub output: Option<String>,
+}
+
+/// Expand all placeholders in a template
+///
+/// Supported placeholders:
+/// - `{clipboard}` - current clipboard text
+/// - `{primary_selection}` - currently selected text (X11/Wayland primary
+///   selection; the clipboard on other platforms)
+/// - `{date}` / `{date:FORMAT}` - local date (default `%Y-%m-%d`)
+/// - `{time}` / `{time:FORMAT}` - local time (default `%H:%M:%S`)
+/// - `{env:NAME}` - environment variable of the app (empty if unset)
+/// - `{config_dir}` - directory containing the configuration file
+/// - `{prompt:Label}` - value entered by the user; asked once per label
+/// - `{hotkey_name}` - name of the triggering hotkey
+/// - `{ai_response}` - response of the most recent AI call
+/// - `{exit_code}` - exit code of the most recent program that was waited on
+/// - `{output}` - output of the previous step (program stdout or AI response)
+pub fn expand(
+    template: &str,
+    context: &TemplateContext,
+    backends: &mut Backends,
+) -> Result<String, AppError> {
+    expand_cached(template, context, backends, &mut Cache::default())
+}
+
+/// Expand the placeholders in a program's arguments
+/// Each argument stays exactly one argv item, so expanded values never need
+/// quoting. Empty arguments in the configuration are dropped here, while
+/// arguments that expand to an empty string are kept; launchers pass the
+/// result through verbatim.
+pub fn expand_program(
+    program: &ProgramConfig,
+    context: &TemplateContext,
+    backends: &mut Backends,
+) -> Result<ProgramConfig, AppError> {
+    let mut cache = Cache::default();
+    let arguments = program
+        .arguments
+        .iter()
+        .filter(|argument| !argument.is_empty())
+        .map(|argument| expand_cached(argument, context, backends, &mut cache))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(ProgramConfig {
+        arguments,
+        ..program.clone()
+    })
+}
+
+fn expand_cached(
+    template: &str,
+    context: &TemplateContext,
+    backends: &mut Backends,
+    cache: &mut Cache,
+) -> Result<String, AppError> {
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        output.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+
+        let Some(end) = after.find('}') else {
+            // Unterminated brace, keep the remainder as-is
+            output.push_str(&rest[start..]);
+            return Ok(output);
+        };
+
+        let placeholder = &after[..end];
+        let (name, argument) = match placeholder.split_once(':') {
+            Some((name, argument)) => (name, Some(argument)),
+            None => (placeholder, None),
+        };
+
+        match (name, argument) {
+            ("clipboard", _) => {
+                if cache.clipboard.is_none() {
+                    cache.clipboard = Some(backends.clipboard.get_text()?);
+                }
+                output.push_str(cache.clipboard.as_deref().unwrap_or_default());
+            }
+            ("primary_selection", _) => {
+                if cache.primary_selection.is_none() {
+                    cache.primary_selection = Some(backends.clipboard.get_primary_selection()?);
+                }
+                output.push_str(cache.primary_selection.as_deref().unwrap_or_default());
+            }
+            ("date", _) => push_formatted_now(&mut output, argument.unwrap_or("%Y-%m-%d"))?,
+            ("time", _) => push_formatted_now(&mut output, argument.unwrap_or("%H:%M:%S"))?,
+            ("env", Some(variable)) => {
+                output.push_str(&std::env::var(variable).unwrap_or_default());
+            }
+            ("config_dir", _) => {
+                let dir = crate::config::manager::get_config_dir()?;
+                output.push_str(&dir.to_string_lossy());
+            }
+            ("prompt", Some(label)) => {
+                if !cache.answers.contains_key(label) {
+                    let answer = backends.prompt.ask(label)?;
+                    cache.answers.insert(label.to_string(), answer);
+                }
+                output.push_str(&cache.answers[label]);
+            }
+            ("hotkey_name", _) => {
+                output.push_str(context.hotkey_name.as_deref().unwrap_or_default())
+            }
+            ("ai_response", _) => {
+                output.push_str(context.ai_response.as_deref().unwrap_or_default())
+            }
+            ("output", _) => output.push_str(context.output.as_deref().unwrap_or_default()),
+            ("exit_code", _) => {
+                if let Some(code) = context.exit_code {
+                    output.push_str(&code.to_string());
+                }
+            }
+            _ => {
+                output.push('{');
+                output.push_str(placeholder);
+                output.push('}');
+            }
+        }
+
+        rest = &after[end + 1..];
+    }
+
+    output.push_str(rest);
+    Ok(output)
+}
+
+/// Append the current local time using a strftime format
+fn push_formatted_now(output: &mut String, format: &str) -> Result<(), AppError> {
+    // Invalid format strings surface as a formatting error instead of a panic
+    write!(output, "{}", Local::now().format(format))
+        .map_err(|_| AppError::PostAction(format!("Invalid date/time format: {}", format)))
+}
@@ -14,9 +14,11 @@ use tauri::{
 use tauri_plugin_autostart::ManagerExt;
 use tauri_plugin_notification::NotificationExt;
 
-use crate::config::schema::HotkeyConfig;
+use crate::backend::Backends;
+use crate::config::schema::{HotkeyConfig, ProgramConfig};
 use crate::error::AppError;
 use crate::hotkey;
+use crate::template::{self, TemplateContext};
 
 /// Tray icon state
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -264,6 +266,15 @@ fn build_menu(app: &AppHandle, hotkeys: &[HotkeyConfig]) -> Result<Menu<Wry>, Ap
     let quit_item = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)
         .map_err(|e| AppError::Tray(format!("Failed to create quit item: {}", e)))?;
 
+    // Recording controls while a recording session is active
+    let recording_submenu = build_recording_submenu(app)?;
+
+    // Programs launched by hotkeys that are still running
+    let processes_submenu = build_processes_submenu(app)?;
+
+    // Latest output of hotkeys that log their program
+    let logs_submenu = build_logs_submenu(app, hotkeys)?;
+
     // Separators
     let sep1 = PredefinedMenuItem::separator(app)
         .map_err(|e| AppError::Tray(format!("Failed to create separator: {}", e)))?;
@@ -271,19 +282,130 @@ fn build_menu(app: &AppHandle, hotkeys: &[HotkeyConfig]) -> Result<Menu<Wry>, Ap
         .map_err(|e| AppError::Tray(format!("Failed to create separator: {}", e)))?;
 
     // Build complete menu
-    Menu::with_items(
+    let mut items: Vec<&dyn tauri::menu::IsMenuItem<Wry>> = vec![&hotkeys_submenu];
+    if let Some(recording_submenu) = recording_submenu.as_ref() {
+        items.push(recording_submenu);
+    }
+    if let Some(processes_submenu) = processes_submenu.as_ref() {
+        items.push(processes_submenu);
+    }
+    if let Some(logs_submenu) = logs_submenu.as_ref() {
+        items.push(logs_submenu);
+    }
+    items.extend([
+        &sep1 as &dyn tauri::menu::IsMenuItem<Wry>,
+        &settings_item,
+        &import_export_submenu,
+        &sep2,
+        &autostart_item,
+        &quit_item,
+    ]);
+
+    Menu::with_items(app, &items)
+        .map_err(|e| AppError::Tray(format!("Failed to create menu: {}", e)))
+}
+
+/// Build the Recording submenu (None when no recording session is active)
+fn build_recording_submenu(app: &AppHandle) -> Result<Option<Submenu<Wry>>, AppError> {
+    use crate::audio::service::{self, RecordingOwner, RecordingState};
+
+    let status = service::status();
+    let (title, pause_label, pause_id) = match status.state {
+        RecordingState::Recording => ("Recording", "Pause", "recording_pause"),
+        RecordingState::Paused => ("Recording (Paused)", "Resume", "recording_resume"),
+        _ => return Ok(None),
+    };
+
+    let pause_item = MenuItem::with_id(app, pause_id, pause_label, true, None::<&str>)
+        .map_err(|e| AppError::Tray(format!("Failed to create pause item: {}", e)))?;
+
+    // Only hotkey sessions know how to process their recording
+    let stop_item = MenuItem::with_id(
         app,
-        &[
-            &hotkeys_submenu,
-            &sep1,
-            &settings_item,
-            &import_export_submenu,
-            &sep2,
-            &autostart_item,
-            &quit_item,
-        ],
+        "recording_stop",
+        "Stop and Process",
+        matches!(status.owner, Some(RecordingOwner::Hotkey { .. })),
+        None::<&str>,
     )
-    .map_err(|e| AppError::Tray(format!("Failed to create menu: {}", e)))
+    .map_err(|e| AppError::Tray(format!("Failed to create stop item: {}", e)))?;
+
+    let discard_item =
+        MenuItem::with_id(app, "recording_discard", "Discard", true, None::<&str>)
+            .map_err(|e| AppError::Tray(format!("Failed to create discard item: {}", e)))?;
+
+    Submenu::with_items(app, title, true, &[&pause_item, &stop_item, &discard_item])
+        .map(Some)
+        .map_err(|e| AppError::Tray(format!("Failed to create recording submenu: {}", e)))
+}
+
+/// Build the Running Programs submenu (None when no tracked program is running)
+/// Clicking a program kills it.
+fn build_processes_submenu(app: &AppHandle) -> Result<Option<Submenu<Wry>>, AppError> {
+    let running: Vec<_> = crate::process::tracker::list()
+        .into_iter()
+        .filter(|p| p.is_running())
+        .collect();
+
+    if running.is_empty() {
+        return Ok(None);
+    }
+
+    let mut items: Vec<MenuItem<Wry>> = Vec::new();
+    for process in &running {
+        let label = format!("Kill {} (PID {})", process.hotkey_name, process.pid);
+        let item_id = format!("kill_process_{}", process.pid);
+
+        let item = MenuItem::with_id(app, &item_id, &label, true, None::<&str>)
+            .map_err(|e| AppError::Tray(format!("Failed to create process item: {}", e)))?;
+        items.push(item);
+    }
+
+    let item_refs: Vec<&dyn tauri::menu::IsMenuItem<Wry>> = items
+        .iter()
+        .map(|i| i as &dyn tauri::menu::IsMenuItem<Wry>)
+        .collect();
+
+    Submenu::with_items(app, "Running Programs", true, &item_refs)
+        .map(Some)
+        .map_err(|e| AppError::Tray(format!("Failed to create processes submenu: {}", e)))
+}
+
+/// Build the View Log submenu (None when no hotkey logs its program's output)
+fn build_logs_submenu(
+    app: &AppHandle,
+    hotkeys: &[HotkeyConfig],
+) -> Result<Option<Submenu<Wry>>, AppError> {
+    use crate::config::schema::HotkeyAction;
+
+    let mut items: Vec<MenuItem<Wry>> = Vec::new();
+    for hk in hotkeys {
+        let (HotkeyAction::LaunchProgram { program }
+        | HotkeyAction::FilterClipboard { program, .. }) = &hk.action
+        else {
+            continue;
+        };
+        if !program.log_output {
+            continue;
+        }
+
+        let item_id = format!("view_log_{}", hk.id);
+        let item = MenuItem::with_id(app, &item_id, &hk.name, true, None::<&str>)
+            .map_err(|e| AppError::Tray(format!("Failed to create log item: {}", e)))?;
+        items.push(item);
+    }
+
+    if items.is_empty() {
+        return Ok(None);
+    }
+
+    let item_refs: Vec<&dyn tauri::menu::IsMenuItem<Wry>> = items
+        .iter()
+        .map(|i| i as &dyn tauri::menu::IsMenuItem<Wry>)
+        .collect();
+
+    Submenu::with_items(app, "View Log", true, &item_refs)
+        .map(Some)
+        .map_err(|e| AppError::Tray(format!("Failed to create logs submenu: {}", e)))
 }
 
 /// Build the hotkeys submenu
@@ -350,7 +472,7 @@ fn build_import_export_submenu(app: &AppHandle) -> Result<Submenu<Wry>, AppError
 }
 
 /// Handle tray menu events
-fn handle_menu_event(app: &AppHandle, id: &str) {
+pub fn handle_menu_event(app: &AppHandle, id: &str) {
     match id {
         "settings" => {
             // Show the settings window
@@ -390,6 +512,24 @@ fn handle_menu_event(app: &AppHandle, id: &str) {
             // Exit the application
             app.exit(0);
         }
+        "recording_pause" | "recording_resume" | "recording_stop" | "recording_discard" => {
+            handle_recording_menu_event(id);
+        }
+        id if id.starts_with("view_log_") => {
+            // Show the log in the settings window
+            if let Some(window) = app.get_webview_window("main") {
+                let _ = window.show();
+                let _ = window.set_focus();
+            }
+            let _ = app.emit("tray-show-log", &id["view_log_".len()..]);
+        }
+        id if id.starts_with("kill_process_") => {
+            if let Ok(pid) = id["kill_process_".len()..].parse::<u32>() {
+                if let Err(e) = crate::process::tracker::kill(pid) {
+                    eprintln!("Failed to kill process {}: {}", pid, e);
+                }
+            }
+        }
         id if id.starts_with("hotkey_") => {
             // Execute hotkey's program
             let hotkey_id = &id[7..]; // Remove "hotkey_" prefix
@@ -399,41 +539,50 @@ fn handle_menu_event(app: &AppHandle, id: &str) {
     }
 }
 
+/// Control the active recording session from the tray menu
+fn handle_recording_menu_event(id: &str) {
+    use crate::audio::service::{self, RecordingOwner};
+
+    let result = match id {
+        "recording_pause" => service::pause(),
+        "recording_resume" => service::resume(),
+        "recording_discard" => service::discard(),
+        "recording_stop" => {
+            // Stopping a hotkey session works like pressing its hotkey again
+            if let Some(RecordingOwner::Hotkey { id }) = service::active_owner() {
+                hotkey::manager::trigger(&id);
+            }
+            Ok(())
+        }
+        _ => Ok(()),
+    };
+
+    if let Err(e) = result {
+        eprintln!("Failed to control recording: {}", e);
+    }
+}
+
 /// Execute a program associated with a hotkey ID
 fn execute_hotkey_program(id: &str) {
     use crate::config::schema::HotkeyAction;
 
     let registry = hotkey::manager::REGISTRY.read().unwrap();
     if let Some((_, _, config)) = registry.get(id) {
-        let action = config.action.clone();
-        let hotkey_name = config.name.clone();
+        let config = config.clone();
 
         std::thread::spawn(move || {
-            if let HotkeyAction::LaunchProgram { program } = action {
-                if let Err(e) = crate::process::spawner::launch(&program) {
-                    eprintln!(
-                        "Failed to launch program for hotkey '{}': {}",
-                        hotkey_name, e
-                    );
-                }
+            if let HotkeyAction::LaunchProgram { program } = &config.action {
+                launch_with_arguments(program, &config);
             }
-            // Note: CallAi actions are not supported from tray menu
+            // Note: CallAi and FilterClipboard actions are not supported from tray menu
         });
     } else {
         // Hotkey not in registry (maybe disabled), try to find in config
         if let Ok(config) = crate::config::manager::load_config() {
-            if let Some(hk) = config.hotkeys.iter().find(|h| h.id == id) {
-                let action = hk.action.clone();
-                let hotkey_name = hk.name.clone();
-
+            if let Some(hk) = config.hotkeys.into_iter().find(|h| h.id == id) {
                 std::thread::spawn(move || {
-                    if let HotkeyAction::LaunchProgram { program } = action {
-                        if let Err(e) = crate::process::spawner::launch(&program) {
-                            eprintln!(
-                                "Failed to launch program for hotkey '{}': {}",
-                                hotkey_name, e
-                            );
-                        }
+                    if let HotkeyAction::LaunchProgram { program } = &hk.action {
+                        launch_with_arguments(program, &hk);
                     }
                 });
             }
@@ -441,6 +590,28 @@ fn execute_hotkey_program(id: &str) {
     }
 }
 
+/// Expand a program's argument placeholders and launch it, tracked under the hotkey
+fn launch_with_arguments(program: &ProgramConfig, hotkey: &HotkeyConfig) {
+    let context = TemplateContext {
+        hotkey_name: Some(hotkey.name.clone()),
+        ..Default::default()
+    };
+    let mut backends = Backends::live_for(hotkey);
+
+    let result = match backends.check_instance_policy(program, &hotkey.name) {
+        Ok(true) => template::expand_program(program, &context, &mut backends)
+            .and_then(|program| backends.process.launch(&program)),
+        other => other.map(|_| ()),
+    };
+
+    if let Err(e) = result {
+        eprintln!(
+            "Failed to launch program for hotkey '{}': {}",
+            hotkey.name, e
+        );
+    }
+}
+
 /// Update the tray menu with current hotkeys
 pub fn update_menu(app: &AppHandle, hotkeys: &[HotkeyConfig]) -> Result<(), AppError> {
     let tray_ref = TRAY.read().unwrap();
@@ -454,6 +625,33 @@ pub fn update_menu(app: &AppHandle, hotkeys: &[HotkeyConfig]) -> Result<(), AppE
     Ok(())
 }
 
+/// Rebuild the tray menu from the saved configuration on the main thread
+pub fn refresh_menu() {
+    let app_handle = {
+        let handle = APP_HANDLE.read().unwrap();
+        handle.clone()
+    };
+
+    if let Some(app) = app_handle {
+        let app_clone = app.clone();
+        let _ = app.run_on_main_thread(move || {
+            if let Ok(config) = crate::config::manager::load_config() {
+                if let Err(e) = update_menu(&app_clone, &config.hotkeys) {
+                    eprintln!("Failed to refresh tray menu: {}", e);
+                }
+            }
+        });
+    }
+}
+
+/// Set the tray icon tooltip
+pub fn set_tooltip(text: &str) {
+    let tray_ref = TRAY.read().unwrap();
+    if let Some(tray) = tray_ref.as_ref() {
+        let _ = tray.set_tooltip(Some(text));
+    }
+}
+
 /// Check if autostart is enabled
 pub fn is_autostart_enabled(app: &AppHandle) -> bool {
     app.autolaunch().is_enabled().unwrap_or(false)
@@ -571,3 +769,17 @@ pub fn send_notification(title: &str, body: &str) {
         }
     }
 }
+
+/// Emit an event to all frontend windows
+pub fn emit_event<S: serde::Serialize + Clone>(event: &str, payload: S) {
+    let app_handle = {
+        let handle = APP_HANDLE.read().unwrap();
+        handle.clone()
+    };
+
+    if let Some(app) = app_handle {
+        if let Err(e) = app.emit(event, payload) {
+            eprintln!("Failed to emit event '{}': {}", event, e);
+        }
+    }
+}
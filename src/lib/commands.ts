@@ -11,6 +11,12 @@ import type {
   HotkeyBinding,
   ProgramConfig,
   AiRole,
+  ArchivedRecording,
+  RecordingStatus,
+  ExecutionReport,
+  DryRunReport,
+  TrackedProcess,
+  DesktopApp,
 } from './types';
 
 // ============================================================================
@@ -137,6 +143,27 @@ export async function launchProgram(config: ProgramConfig): Promise<void> {
   return invoke('launch_program', { config });
 }
 
+/**
+ * Get the programs launched by hotkeys, oldest first
+ */
+export async function getProcesses(): Promise<TrackedProcess[]> {
+  return invoke<TrackedProcess[]>('get_processes');
+}
+
+/**
+ * Kill a running program launched by a hotkey, together with its children
+ */
+export async function killProcess(pid: number): Promise<void> {
+  return invoke('kill_process', { pid });
+}
+
+/**
+ * Get the end of a hotkey's program log
+ */
+export async function getProgramLog(hotkeyId: string): Promise<string> {
+  return invoke<string>('get_program_log', { hotkeyId });
+}
+
 /**
  * Validate that a program path exists and is executable
  */
@@ -151,6 +178,13 @@ export async function getExecutableExtensions(): Promise<string[]> {
   return invoke<string[]>('get_executable_extensions');
 }
 
+/**
+ * List installed applications (desktop entries on Linux)
+ */
+export async function listApplications(): Promise<DesktopApp[]> {
+  return invoke<DesktopApp[]>('list_applications');
+}
+
 // ============================================================================
 // System Tray Commands
 // ============================================================================
@@ -239,6 +273,8 @@ export interface AudioRecordingResult {
   data: string;
   /** MIME type (audio/ogg for Opus, audio/wav for fallback) */
   mime_type: string;
+  /** ID in the recording archive, if archiving is enabled */
+  archive_id: string | null;
 }
 
 /**
@@ -256,6 +292,34 @@ export async function isAudioRecording(): Promise<boolean> {
   return invoke('is_audio_recording');
 }
 
+/**
+ * Get the recording service state
+ */
+export async function getRecordingStatus(): Promise<RecordingStatus> {
+  return invoke<RecordingStatus>('get_recording_status');
+}
+
+/**
+ * Pause the active recording
+ */
+export async function pauseAudioRecording(): Promise<void> {
+  return invoke('pause_audio_recording');
+}
+
+/**
+ * Resume a paused recording
+ */
+export async function resumeAudioRecording(): Promise<void> {
+  return invoke('resume_audio_recording');
+}
+
+/**
+ * Stop the active recording without sending it
+ */
+export async function discardAudioRecording(): Promise<void> {
+  return invoke('discard_audio_recording');
+}
+
 /**
  * Send audio to AI for transcription/processing
  */
@@ -268,3 +332,82 @@ export async function sendAudioToAi(
 ): Promise<string> {
   return invoke('send_audio_to_ai', { apiKey, model, systemPrompt, audioBase64, mimeType });
 }
+
+// ============================================================================
+// Post-Action Report Commands
+// ============================================================================
+
+/**
+ * Get reports of recent post-action runs, newest first
+ */
+export async function getPostActionHistory(): Promise<ExecutionReport[]> {
+  return invoke<ExecutionReport[]>('get_post_action_history');
+}
+
+/**
+ * Clear the post-action run history
+ */
+export async function clearPostActionHistory(): Promise<void> {
+  return invoke('clear_post_action_history');
+}
+
+// ============================================================================
+// Dry Run Commands
+// ============================================================================
+
+/**
+ * Dry-run a hotkey and return the timeline of what would have happened
+ */
+export async function dryRunHotkey(id: string): Promise<DryRunReport> {
+  return invoke<DryRunReport>('dry_run_hotkey', { id });
+}
+
+/**
+ * Get recent dry runs, newest first
+ */
+export async function getDryRunHistory(): Promise<DryRunReport[]> {
+  return invoke<DryRunReport[]>('get_dry_run_history');
+}
+
+// ============================================================================
+// Prompt Commands
+// ============================================================================
+
+/**
+ * Get the label of a pending {prompt:Label} placeholder
+ */
+export async function getPrompt(id: string): Promise<string | null> {
+  return invoke<string | null>('get_prompt', { id });
+}
+
+/**
+ * Answer a pending prompt; null cancels it
+ */
+export async function answerPrompt(id: string, value: string | null): Promise<void> {
+  return invoke('answer_prompt', { id, value });
+}
+
+// ============================================================================
+// Recording Archive Commands
+// ============================================================================
+
+/**
+ * List archived recordings, newest first
+ */
+export async function listArchivedRecordings(): Promise<ArchivedRecording[]> {
+  return invoke<ArchivedRecording[]>('list_archived_recordings');
+}
+
+/**
+ * Re-run an archived recording through a role and return the AI response
+ */
+export async function reprocessArchivedRecording(id: string, roleId: string): Promise<string> {
+  return invoke<string>('reprocess_archived_recording', { id, roleId });
+}
+
+/**
+ * Delete an archived recording
+ */
+export async function deleteArchivedRecording(id: string): Promise<void> {
+  return invoke('delete_archived_recording', { id });
+}
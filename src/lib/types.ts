@@ -12,12 +12,93 @@ export interface ProgramConfig {
   arguments: string[];
   workingDirectory?: string;
   hidden: boolean;
+  /** Kill the program after this many milliseconds (waited programs only) */
+  timeoutMs?: number | null;
+  onTimeout?: TimeoutOutcome;
+  /** Start from the app's environment (default true) */
+  inheritEnv?: boolean;
+  /** `.env` file, relative to the working directory */
+  envFile?: string | null;
+  env?: Record<string, EnvValue>;
+  /** What to do when the program is already running (default 'always') */
+  instancePolicy?: InstancePolicy;
+  /** Write stdout and stderr to <config_dir>/logs/<hotkey-id>.log */
+  logOutput?: boolean;
+  /** Launch inside a terminal emulator (Linux) */
+  runInTerminal?: boolean;
+  /** Wait for Enter before the terminal closes */
+  keepTerminalOpen?: boolean;
+  /** Priority and limits (Linux) */
+  resources?: ResourceLimits;
 }
 
+/** CPU and IO priority, rlimits and systemd scope of a program (Linux) */
+export interface ResourceLimits {
+  /** -20 (highest priority) to 19 (lowest) */
+  nice?: number | null;
+  ioPriority?: IoPriority | null;
+  /** Address space limit (RLIMIT_AS) */
+  memoryMb?: number | null;
+  /** CPU time limit (RLIMIT_CPU) */
+  cpuSeconds?: number | null;
+  /** Largest file the program may write (RLIMIT_FSIZE) */
+  fileSizeMb?: number | null;
+  /** Maximum number of open files (RLIMIT_NOFILE) */
+  openFiles?: number | null;
+  /** Run in a transient systemd user scope */
+  systemdScope?: boolean;
+}
+
+export type IoPriority = { type: 'bestEffort'; level: number } | { type: 'idle' };
+
+export type InstancePolicy = 'always' | 'skipIfRunning' | 'restart';
+
+/** Environment override; `set` values may reference `${VAR}` */
+export type EnvValue = { type: 'set'; value: string } | { type: 'unset' } | { type: 'inherit' };
+
+/** How a program killed after its timeout is reported */
+export type TimeoutOutcome = { type: 'fail' } | { type: 'exitCode'; code: number };
+
 // Main action types for hotkeys
 export type HotkeyAction =
   | { type: 'launchProgram'; program: ProgramConfig }
-  | { type: 'callAi'; roleId: string; inputSource: AiInputSource; providerId?: string };
+  | {
+      type: 'callAi';
+      roleId: string;
+      inputSource: AiInputSource;
+      providerId?: string;
+      /** Restore the previous clipboard once the post-actions have run */
+      restoreClipboard?: ClipboardRestore | null;
+    }
+  | {
+      /** Pipe the clipboard into the program's stdin and replace it with stdout */
+      type: 'filterClipboard';
+      program: ProgramConfig;
+      /** Largest clipboard text and output accepted, in bytes */
+      maxBytes: number;
+    }
+  | { type: 'runScript'; script: ScriptConfig };
+
+/** An inline script run by an interpreter */
+export interface ScriptConfig {
+  interpreter: ScriptInterpreter;
+  body: string;
+  workingDirectory?: string | null;
+  hidden?: boolean;
+  /** Kill the script after this many milliseconds when it is waited on */
+  timeoutMs?: number | null;
+  env?: Record<string, EnvValue>;
+}
+
+export type ScriptInterpreter = 'sh' | 'bash' | 'python3' | 'node' | 'pwsh';
+
+/**
+ * Restore the clipboard contents from before an action overwrote them
+ */
+export interface ClipboardRestore {
+  /** Time to wait after the paste before restoring */
+  settleDelayMs: number;
+}
 
 export interface HotkeyConfig {
   id: string;
@@ -28,6 +109,10 @@ export interface HotkeyConfig {
   createdAt: string;
   updatedAt: string;
   postActions?: PostActionsConfig;
+  /** Record what the hotkey would do instead of doing it */
+  dryRun?: boolean;
+  /** Kill the running program instead of launching another one */
+  toggle?: boolean;
 }
 
 /**
@@ -38,6 +123,12 @@ export interface AppSettings {
   showTrayNotifications: boolean;
   /** Custom config location. If undefined, uses default (~/.global-hotkey/) */
   configLocation?: string;
+  /** Run every hotkey in dry-run mode */
+  dryRun?: boolean;
+  /** Disable a hotkey that fires more often than this per second (0 = no limit) */
+  maxTriggersPerSecond?: number;
+  /** Terminal emulator for programs run in a terminal (null = auto-detect) */
+  terminal?: string | null;
 }
 
 /**
@@ -58,7 +149,14 @@ export interface FullConfig {
 }
 
 // Post-Action Types
-export type PostActionTrigger = { type: 'onExit' } | { type: 'afterDelay'; delayMs: number };
+export type PostActionTrigger =
+  | { type: 'onExit' }
+  | { type: 'afterDelay'; delayMs: number }
+  | { type: 'onAnyExit' }
+  | { type: 'onExitCodes'; codes: number[] }
+  | { type: 'onFailure' }
+  /** Regular expression matched against each line of stdout while the process runs */
+  | { type: 'onStdoutMatch'; pattern: string };
 
 export interface Keystroke {
   modifiers: string[];
@@ -66,14 +164,106 @@ export interface Keystroke {
 }
 
 export type PostActionType =
-  | { type: 'pasteClipboard' }
+  | { type: 'pasteClipboard'; restoreClipboard?: ClipboardRestore | null }
   | { type: 'simulateKeystroke'; keystroke: Keystroke }
-  | { type: 'delay'; delayMs: number };
+  | { type: 'delay'; delayMs: number }
+  /** Supports {clipboard}, {date:%Y-%m-%d}, {time}, {hotkey_name} and {ai_response} */
+  | { type: 'typeText'; text: string }
+  /** When waiting for exit, the exit code is available to later steps as {exit_code} */
+  | { type: 'runProgram'; program: ProgramConfig; waitForExit: boolean }
+  /** Runs a role and writes the response to the clipboard */
+  | { type: 'callAi'; roleId: string; input: AiStepInput }
+  /** Low-level keyboard and mouse input */
+  | { type: 'inputScript'; steps: InputStep[] };
+
+/** A single step of an input script; keys may also be modifier names such as "ctrl" */
+export type InputStep =
+  | { type: 'press'; key: string }
+  | { type: 'release'; key: string }
+  | { type: 'tap'; key: string }
+  | { type: 'hold'; key: string; durationMs: number }
+  | { type: 'repeat'; times: number; steps: InputStep[] }
+  | { type: 'typeUnicode'; text: string }
+  | { type: 'wait'; delayMs: number }
+  | { type: 'mouseMove'; x: number; y: number; relative?: boolean }
+  | { type: 'click'; button?: MouseButton }
+  /** Positive amounts scroll down (or right when horizontal) */
+  | { type: 'scroll'; amount: number; horizontal?: boolean };
+
+export type MouseButton = 'left' | 'middle' | 'right';
+
+/** Input for an AI post-action step */
+export type AiStepInput = 'clipboard' | 'previousOutput';
+
+/** What to do when a post-action step fails (defaults to abort) */
+export type OnErrorPolicy =
+  | { type: 'abort' }
+  | { type: 'continue' }
+  | { type: 'retry'; attempts: number };
 
 export interface PostAction {
   id: string;
   actionType: PostActionType;
   enabled: boolean;
+  onError?: OnErrorPolicy;
+}
+
+export type StepStatus = 'succeeded' | 'failed' | 'aborted' | 'skipped';
+
+export interface StepReport {
+  index: number;
+  actionId: string;
+  actionType: PostActionType['type'];
+  status: StepStatus;
+  attempts: number;
+  durationMs: number;
+  error?: string | null;
+}
+
+/**
+ * Report of a post-action run (also emitted as the 'post-action-report' event)
+ */
+export interface ExecutionReport {
+  id: string;
+  hotkeyName: string;
+  startedAt: string;
+  durationMs: number;
+  success: boolean;
+  /** True if the run had no real side effects */
+  dryRun: boolean;
+  steps: StepReport[];
+}
+
+export type TimelineKind =
+  | 'input'
+  | 'clipboard'
+  | 'process'
+  | 'ai'
+  | 'prompt'
+  | 'wait'
+  | 'notification'
+  | 'info';
+
+/** Something that would have happened during a dry run */
+export interface TimelineEntry {
+  /** Simulated time since the start of the run */
+  atMs: number;
+  kind: TimelineKind;
+  description: string;
+}
+
+/** Result of a dry run of one hotkey */
+export interface DryRunReport {
+  id: string;
+  hotkeyId: string;
+  hotkeyName: string;
+  startedAt: string;
+  /** Simulated duration, including waits that were skipped */
+  simulatedMs: number;
+  durationMs: number;
+  success: boolean;
+  error?: string | null;
+  entries: TimelineEntry[];
 }
 
 export interface PostActionsConfig {
@@ -111,6 +301,43 @@ export interface AiSettings {
   providers: AiProviderConfig[];
   defaultProviderId?: string;
   roles: AiRole[];
+  archive?: RecordingArchiveSettings;
+  chunking?: ChunkingSettings;
+}
+
+/**
+ * Splitting of long recordings into sequentially transcribed chunks
+ */
+export interface ChunkingSettings {
+  /** Recordings longer than this are split (seconds, 0 = never split) */
+  thresholdSecs: number;
+  /** Target length of each chunk (seconds) */
+  chunkLengthSecs: number;
+  /** How far around each chunk mark to search for silence (seconds) */
+  silenceSearchSecs: number;
+}
+
+/**
+ * Retention settings for the recording archive (<config_dir>/recordings/)
+ */
+export interface RecordingArchiveSettings {
+  enabled: boolean;
+  /** Maximum total archive size in MB (0 = unlimited) */
+  maxSizeMb: number;
+  /** Maximum age of recordings in days (0 = keep forever) */
+  maxAgeDays: number;
+}
+
+export interface ArchivedRecording {
+  id: string;
+  createdAt: string;
+  mimeType: string;
+  sizeBytes: number;
+  /** Role used for the most recent AI run */
+  roleId: string;
+  result?: string;
+  error?: string;
+  processedAt?: string;
 }
 
 export type AudioFormat = 'opus' | 'wav';
@@ -118,4 +345,70 @@ export type AudioFormat = 'opus' | 'wav';
 export type AiInputSource =
   | { type: 'clipboard' }
   | { type: 'recordAudio'; maxDurationMs: number; format: AudioFormat }
-  | { type: 'processOutput' };
+  | { type: 'processOutput' }
+  | { type: 'audioFile'; path?: string };
+
+/**
+ * Input level report emitted as `recording-level` while recording
+ */
+export interface RecordingLevel {
+  /** RMS level since the last report (0.0 - 1.0) */
+  rms: number;
+  /** Peak level since the last report (0.0 - 1.0) */
+  peak: number;
+  elapsedMs: number;
+  /** Time left until the maximum duration (absent if unlimited) */
+  remainingMs?: number | null;
+  limitReached: boolean;
+}
+
+/**
+ * Who started the active recording session
+ */
+export type RecordingOwner = { type: 'hotkey'; id: string } | { type: 'frontend' };
+
+/**
+ * Recording service state
+ */
+export type RecordingState = 'idle' | 'recording' | 'paused' | 'processing';
+
+/**
+ * Recording service snapshot (also emitted as the 'recording-state' event)
+ */
+export interface RecordingStatus {
+  state: RecordingState;
+  owner: RecordingOwner | null;
+}
+
+// ============================================================================
+// Process Table Types
+// ============================================================================
+
+export type ProcessStatus =
+  | { type: 'running' }
+  | { type: 'exited'; code: number | null }
+  | { type: 'killed' };
+
+/** A program launched by a hotkey */
+export interface TrackedProcess {
+  pid: number;
+  hotkeyId: string;
+  hotkeyName: string;
+  command: string;
+  startedAt: string;
+  status: ProcessStatus;
+  /** Output goes to the hotkey's log */
+  logged: boolean;
+}
+
+/** An installed application from a desktop entry */
+export interface DesktopApp {
+  /** Desktop file id, usable as a program path */
+  id: string;
+  name: string;
+  comment: string | null;
+  icon: string | null;
+  exec: string;
+  /** Path of the .desktop file */
+  file: string;
+}
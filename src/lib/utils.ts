@@ -54,3 +54,53 @@ export function getFilename(path: string): string {
   const parts = path.split(separator);
   return parts[parts.length - 1] || path;
 }
+
+/**
+ * Format a duration in milliseconds as m:ss
+ */
+export function formatDuration(ms: number): string {
+  const totalSeconds = Math.floor(ms / 1000);
+  const minutes = Math.floor(totalSeconds / 60);
+  const seconds = totalSeconds % 60;
+  return `${minutes}:${seconds.toString().padStart(2, '0')}`;
+}
+
+/**
+ * Split an argument line into arguments
+ * Spaces inside double quotes or {placeholders} don't split, so each
+ * argument becomes exactly one argv item.
+ */
+export function splitArguments(line: string): string[] {
+  const args: string[] = [];
+  let current = '';
+  let started = false;
+  let quoted = false;
+  let braces = 0;
+
+  for (const char of line) {
+    if (char === '"' && braces === 0) {
+      quoted = !quoted;
+      started = true;
+    } else if (char === ' ' && !quoted && braces === 0) {
+      if (started) args.push(current);
+      current = '';
+      started = false;
+    } else {
+      if (char === '{') braces++;
+      if (char === '}' && braces > 0) braces--;
+      current += char;
+      started = true;
+    }
+  }
+  if (started) args.push(current);
+  return args;
+}
+
+/**
+ * Join arguments into a line that splitArguments turns back into the same arguments
+ */
+export function joinArguments(args: string[]): string {
+  return args
+    .map((arg) => (arg === '' || /\s/.test(arg.replace(/\{[^}]*\}/g, '')) ? `"${arg}"` : arg))
+    .join(' ');
+}
@@ -1,9 +1,15 @@
 import './app.css';
 import App from './App.svelte';
+import RecordingIndicator from './components/RecordingIndicator.svelte';
+import PromptDialog from './components/PromptDialog.svelte';
 import { mount } from 'svelte';
 
-const app = mount(App, {
-  target: document.getElementById('app')!,
-});
+// The recording indicator and prompt windows load the same bundle with their own routes
+const hash = window.location.hash;
+const target = document.getElementById('app')!;
+
+const app = hash.startsWith('#prompt/')
+  ? mount(PromptDialog, { target, props: { id: hash.slice('#prompt/'.length) } })
+  : mount(hash === '#recording-indicator' ? RecordingIndicator : App, { target });
 
 export default app;
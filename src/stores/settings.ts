@@ -10,6 +10,9 @@ const defaultSettings: AppSettings = {
   startWithSystem: false,
   showTrayNotifications: true,
   configLocation: undefined,
+  dryRun: false,
+  maxTriggersPerSecond: 10,
+  terminal: null,
 };
 
 export const settings = writable<AppSettings>(defaultSettings);